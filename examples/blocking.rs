@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::ThreadId;
+use std::time::Duration;
+use std::time::Instant;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Blocking;
+use dag_flow::engine::Engine;
+use dag_flow::engine::Spawner;
+use dag_flow::engine::ThreadPool;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::channel::oneshot;
+use futures::executor;
+use futures::future::BoxFuture;
+use futures_timer::Delay;
+
+const WORK: Duration = Duration::from_millis(50);
+
+static THREADS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+
+fn main() {
+    let builder = Engine::builder();
+    builder
+        .add_task(Blocking::new(Busy("a")))
+        .add_task(Blocking::new(Busy("b")))
+        .add_task(Sum)
+        .add_task(Ticks)
+        .spawner(ThreadPool::new(2));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+
+    let now = Instant::now();
+    executor::block_on(engine.run(context.clone()));
+    assert!(now.elapsed() < WORK * 3);
+
+    let outputs = executor::block_on(async {
+        (
+            context.get(&"sum".into()).unwrap().await,
+            context.get(&"ticks".into()).unwrap().await,
+        )
+    });
+
+    // The ticker keeps running on the calling thread while both tasks block.
+    assert_eq!(outputs.0, Some(3));
+    assert!(outputs.1.unwrap() >= 3);
+
+    let threads = THREADS.lock().unwrap();
+    assert_eq!(threads.len(), 3);
+    assert!(!threads.contains(&thread::current().id()));
+    drop(threads);
+
+    // Without a spawner, or with one that keeps the default `spawn_blocking`,
+    // the tasks still run under `block_on`.
+    for spawner in [None, Some(Detached)] {
+        let builder = Engine::builder();
+        builder
+            .add_task(Blocking::new(Busy("a")))
+            .add_task(Blocking::new(Busy("b")))
+            .add_task(Sum);
+
+        if let Some(spawner) = spawner {
+            builder.spawner(spawner);
+        }
+
+        let engine = builder.build().unwrap();
+        let context = Context::new();
+        executor::block_on(engine.run(context.clone()));
+        assert_eq!(
+            executor::block_on(context.get(&"sum".into()).unwrap()),
+            Some(3)
+        );
+    }
+
+    // A panicking job cancels its result without taking its thread down, so
+    // the single thread still runs the jobs after it.
+    panic::set_hook(Box::new(|_| {}));
+    let pool = ThreadPool::new(1);
+
+    let (sender, panicked) = oneshot::channel::<()>();
+    pool.spawn_blocking(Box::new(move || {
+        let _sender = sender;
+        panic!("blocking job");
+    }));
+
+    let (future, handle) = async { panic!("spawned future") }.remote_handle();
+    pool.spawn(future.boxed());
+
+    let (sender, finished) = oneshot::channel();
+    pool.spawn_blocking(Box::new(move || {
+        let _ = sender.send(thread::current().id());
+    }));
+
+    assert!(executor::block_on(panicked).is_err());
+    assert!(executor::block_on(AssertUnwindSafe(handle).catch_unwind()).is_err());
+    assert!(executor::block_on(finished).is_ok());
+    let _ = panic::take_hook();
+}
+
+// Drives each future on a thread of its own.
+struct Detached;
+
+impl Spawner for Detached {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        thread::spawn(move || executor::block_on(future));
+    }
+}
+
+struct Busy(&'static str);
+
+impl Task<String, u64> for Busy {
+    fn id(&self) -> String {
+        self.0.into()
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, u64>>, _: Scope<String, u64>) -> Option<u64> {
+        THREADS.lock().unwrap().push(thread::current().id());
+        thread::sleep(WORK);
+
+        Some(1)
+    }
+}
+
+struct Sum;
+
+impl Task<String, u64> for Sum {
+    fn id(&self) -> String {
+        "sum".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["a".into(), "b".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        let a = inputs["a"].clone().await?;
+        let b = inputs["b"].clone().await?;
+
+        scope
+            .spawn_blocking(move || {
+                THREADS.lock().unwrap().push(thread::current().id());
+                Some(a + b + 1)
+            })
+            .await
+    }
+}
+
+struct Ticks;
+
+impl Task<String, u64> for Ticks {
+    fn id(&self) -> String {
+        "ticks".into()
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, u64>>, _: Scope<String, u64>) -> Option<u64> {
+        let mut ticks = 0;
+        let now = Instant::now();
+
+        while now.elapsed() < WORK {
+            Delay::new(Duration::from_millis(5)).await;
+            ticks += 1;
+        }
+
+        Some(ticks)
+    }
+}
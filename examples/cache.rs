@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use dag_flow::context::Context;
+use dag_flow::engine::CacheStatus;
+use dag_flow::engine::Engine;
+use dag_flow::engine::LruCache;
+use dag_flow::engine::TaskCache;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::executor;
+use futures::future;
+
+fn main() {
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let builder = Engine::builder();
+    builder
+        .add_input("x".into())
+        .add_task(Square(runs.clone()))
+        .add_task(Double)
+        .cache(LruCache::new(2));
+
+    let engine = builder.build().unwrap();
+
+    // With room for two outputs, the least recently used one is evicted: 3
+    // evicts 2 since 1 was used more recently, and 2 then misses again.
+    let cache_status = |x| {
+        let context = Context::new();
+        context.set("x".into(), future::ready(Some(x)).boxed().shared());
+
+        let report = executor::block_on(engine.run(context.clone()));
+        let square = report.tasks[&"square".to_string()];
+        let output = executor::block_on(context.get(&"square".into()).unwrap());
+        assert_eq!(output, Some(x * x));
+
+        // Tasks that are not cacheable never touch the cache.
+        assert_eq!(report.tasks[&"double".to_string()].cache, None);
+        square.cache.unwrap()
+    };
+
+    let statuses: Vec<_> = [1, 2, 1, 3, 1, 2].into_iter().map(cache_status).collect();
+    assert_eq!(
+        statuses,
+        [
+            CacheStatus::Miss,
+            CacheStatus::Miss,
+            CacheStatus::Hit,
+            CacheStatus::Miss,
+            CacheStatus::Hit,
+            CacheStatus::Miss,
+        ]
+    );
+
+    // Only misses run the task.
+    assert_eq!(runs.load(Ordering::Relaxed), 4);
+
+    // The same eviction, driven through `TaskCache` directly.
+    let cache = LruCache::new(2);
+    for fingerprint in 1..=2 {
+        cache.insert("square".to_string(), fingerprint, Some(fingerprint));
+    }
+
+    assert_eq!(cache.get(&"square".into(), 1), Some(Some(1)));
+    cache.insert("square".into(), 3, Some(3));
+    assert_eq!(cache.len(), cache.capacity());
+    assert_eq!(cache.get(&"square".into(), 2), None);
+    assert_eq!(cache.get(&"square".into(), 1), Some(Some(1)));
+}
+
+struct Square(Arc<AtomicUsize>);
+
+impl Task<String, u64> for Square {
+    fn id(&self) -> String {
+        "square".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["x".into()]
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        let x = inputs["x"].clone().await?;
+        Some(x * x)
+    }
+}
+
+struct Double;
+
+impl Task<String, u64> for Double {
+    fn id(&self) -> String {
+        "double".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["x".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        Some(inputs["x"].clone().await? * 2)
+    }
+}
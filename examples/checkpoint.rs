@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::process;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::FsCheckpointStore;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    let dir = env::temp_dir().join(format!("dag-flow-checkpoint-{}", process::id()));
+    let store = || {
+        FsCheckpointStore::new(
+            &dir,
+            |data: &u64| data.to_le_bytes().into(),
+            |bytes| {
+                Ok(u64::from_le_bytes(
+                    bytes
+                        .try_into()
+                        .map_err(|_| io::Error::from(ErrorKind::InvalidData))?,
+                ))
+            },
+        )
+        .unwrap()
+    };
+
+    let builder = Engine::builder();
+    builder
+        .add_task(Step::from("extract", None))
+        .add_task(Step::from("transform", Some("extract")))
+        .add_task(Step::from("load", Some("transform")));
+
+    let engine = builder.build().unwrap();
+
+    let context = Context::new();
+    let report = executor::block_on(engine.resume(context.clone(), store())).unwrap();
+    assert_eq!(report.tasks.len(), 3);
+    assert_eq!(RUNS.load(Ordering::Relaxed), 3);
+
+    let context = Context::new();
+    let report = executor::block_on(engine.resume(context.clone(), store())).unwrap();
+    assert!(report.tasks.is_empty());
+    assert_eq!(RUNS.load(Ordering::Relaxed), 3);
+
+    assert_eq!(
+        executor::block_on(context.get(&"load".into()).unwrap()),
+        Some(3)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+struct Step {
+    id: String,
+    dependency: Option<String>,
+}
+
+impl Step {
+    fn from(id: &str, dependency: Option<&str>) -> Self {
+        Self {
+            id: id.into(),
+            dependency: dependency.map(Into::into),
+        }
+    }
+}
+
+impl Task<String, u64> for Step {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependency.iter().cloned().collect()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        RUNS.fetch_add(1, Ordering::Relaxed);
+
+        match &self.dependency {
+            Some(dependency) => Some(inputs[dependency].clone().await? + 1),
+            None => Some(1),
+        }
+    }
+}
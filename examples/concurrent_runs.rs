@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+use futures::future;
+use futures_timer::Delay;
+
+const RUNS: u64 = 16;
+
+fn main() {
+    let builder = Engine::builder();
+    builder.add_task(RunId).add_task(Double);
+
+    let engine = builder.build().unwrap();
+    let contexts: Vec<_> = (0..RUNS).map(|_| Context::new()).collect();
+
+    let reports = executor::block_on(future::join_all(
+        contexts.iter().map(|context| engine.run(context.clone())),
+    ));
+
+    assert_eq!(engine.runs(), RUNS);
+
+    let run_ids: HashSet<_> = reports.iter().map(|report| report.run_id).collect();
+    assert_eq!(run_ids.len() as u64, RUNS);
+
+    for (context, report) in contexts.iter().zip(&reports) {
+        let data = executor::block_on(async {
+            (
+                context.get(&RunId::id()).unwrap().await,
+                context.get(&Double::id()).unwrap().await,
+            )
+        });
+
+        assert_eq!(data, (Some(report.run_id), Some(report.run_id * 2)));
+        assert_eq!(report.tasks.len(), 2);
+    }
+
+    let stats = engine.stats();
+    for id in [RunId::id(), Double::id()] {
+        assert_eq!(stats[&id].count(), RUNS);
+        assert!(stats[&id].min().unwrap() >= Duration::from_millis(10));
+    }
+}
+
+struct RunId;
+
+impl RunId {
+    fn id() -> String {
+        "run-id".into()
+    }
+}
+
+impl Task<String, u64> for RunId {
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        Delay::new(Duration::from_millis(10)).await;
+        Some(scope.run_id())
+    }
+}
+
+struct Double;
+
+impl Double {
+    fn id() -> String {
+        "double".into()
+    }
+}
+
+impl Task<String, u64> for Double {
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec![RunId::id()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let run_id = inputs[&RunId::id()].clone().await?;
+        Delay::new(Duration::from_millis(10)).await;
+        Some(run_id * 2)
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use dag_flow::dag::Dag;
+use dag_flow::dag::DagBuilder;
+use dag_flow::dag::Edge;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+
+fn main() {
+    let mut builder = Dag::builder();
+    for (from, to) in [("a", "b"), ("b", "c"), ("a", "c"), ("c", "d")] {
+        builder.add_edge(Edge::new(from, to));
+    }
+
+    builder.add_node("e");
+    let dag = builder.build().unwrap();
+
+    assert_eq!(dag.len(), 5);
+    assert!(dag.contains_edge(&"a", &"c"));
+    assert!(!dag.contains_edge(&"c", &"a"));
+    assert_eq!(dag.edges().count(), 4);
+
+    let order = dag.topological_order();
+    let position = |node| order.iter().position(|n| *n == node).unwrap();
+    for Edge { from, to } in dag.edges() {
+        assert!(position(*from) < position(*to));
+    }
+
+    let mut builder = dag.into_builder();
+    builder
+        .remove_edge(&Edge::new("a", "c"))
+        .remove_node(&"d")
+        .add_edge(Edge::new("e", "a"));
+
+    let dag = builder.build().unwrap();
+    let edges: HashSet<_> = dag.edges().map(|Edge { from, to }| (*from, *to)).collect();
+
+    assert_eq!(edges, HashSet::from([("e", "a"), ("a", "b"), ("b", "c")]));
+    assert_eq!(dag.topological_order(), ["e", "a", "b", "c"]);
+
+    let mut builder = dag.clone().into_builder();
+    builder.add_edge(Edge::new("c", "e"));
+    assert!(builder.build().is_err());
+
+    let mut builder = dag.into_builder();
+    builder
+        .add_edge(Edge::new("e", "c"))
+        .add_edge(Edge::new("a", "c"));
+
+    let dag = builder.build().unwrap();
+    let redundant_edges: HashSet<_> = dag.redundant_edges().into_iter().collect();
+    assert_eq!(
+        redundant_edges,
+        HashSet::from([Edge::new("e", "c"), Edge::new("a", "c")])
+    );
+
+    let reduction = dag.transitive_reduction();
+    assert_eq!(reduction.edges().count(), 3);
+    assert!(!reduction.contains_edge(&"e", &"c"));
+
+    let mut builder: DagBuilder<&str, &str, Kind> = DagBuilder::default();
+    builder
+        .add_node_with("fetch", "Fetch sources")
+        .add_edge_with(Edge::new("fetch", "build"), Kind::Hard)
+        .add_edge(Edge::new("build", "test"))
+        .try_add_edge_with(Edge::new("fetch", "test"), Kind::Soft)
+        .unwrap()
+        .add_edge(Edge::new("fetch", "build"));
+
+    assert!(
+        builder
+            .try_add_edge_with(Edge::new("test", "fetch"), Kind::Hard)
+            .is_err()
+    );
+
+    let dag = builder.build().unwrap();
+    assert_eq!(dag.node_value(&"fetch"), Some(&"Fetch sources"));
+    assert_eq!(dag.node_value(&"build"), Some(&""));
+    assert_eq!(dag.edge_value(&"fetch", &"build"), Some(&Kind::Hard));
+    assert_eq!(dag.edge_value(&"fetch", &"test"), Some(&Kind::Soft));
+    assert_eq!(dag.edge_value(&"build", &"test"), None);
+
+    let reduction = dag.transitive_reduction();
+    assert_eq!(reduction.edge_value(&"fetch", &"test"), None);
+    assert_eq!(reduction.edge_value(&"fetch", &"build"), Some(&Kind::Hard));
+
+    let mut builder = dag.into_builder();
+    builder
+        .add_node_with("test", "Run tests")
+        .add_edge_with(Edge::new("build", "test"), Kind::Soft)
+        .remove_edge(&Edge::new("fetch", "build"))
+        .add_edge(Edge::new("fetch", "build"));
+
+    let dag = builder.build().unwrap();
+    assert_eq!(dag.node_value(&"test"), Some(&"Run tests"));
+    assert_eq!(dag.edge_value(&"build", &"test"), Some(&Kind::Soft));
+    assert_eq!(dag.edge_value(&"fetch", &"build"), None);
+
+    let builder = Engine::builder();
+    builder
+        .add_input("input".into())
+        .add_task(Step::from("parse", &["input"]))
+        .add_task(Step::from("check", &["input", "parse"]))
+        .add_task(Step::from("emit", &["input", "parse", "check"]));
+
+    let mut redundant_dependencies = builder.redundant_dependencies().unwrap();
+    redundant_dependencies.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+
+    assert_eq!(
+        redundant_dependencies,
+        [
+            Edge::new("input".into(), "check".into()),
+            Edge::new("input".into(), "emit".into()),
+            Edge::new("parse".into(), "emit".into()),
+        ]
+    );
+
+    // An `after` entry is redundant if its task is reached any other way,
+    // but a data dependency still passes an input when the only other path
+    // goes through an `after` edge.
+    builder
+        .add_task(Step::from("lint", &["parse"]).after(&["input"]))
+        .add_task(Step::from("publish", &["parse"]).after(&["emit"]));
+
+    let mut redundant_dependencies = builder.redundant_dependencies().unwrap();
+    redundant_dependencies.retain(|Edge { to, .. }| to == "lint" || to == "publish");
+    assert_eq!(
+        redundant_dependencies,
+        [Edge::new("input".into(), "lint".into())]
+    );
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Hard,
+    Soft,
+}
+
+struct Step {
+    id: String,
+    dependencies: Vec<String>,
+    after: Vec<String>,
+}
+
+impl Step {
+    fn from(id: &str, dependencies: &[&str]) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.iter().map(|&id| id.into()).collect(),
+            after: Vec::new(),
+        }
+    }
+
+    fn after(mut self, after: &[&str]) -> Self {
+        self.after = after.iter().map(|&id| id.into()).collect();
+        self
+    }
+}
+
+impl Task<String, ()> for Step {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn after(&self) -> Vec<String> {
+        self.after.clone()
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, ()>>, _: Scope<String, ()>) -> Option<()> {
+        None
+    }
+}
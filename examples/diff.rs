@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use dag_flow::dag::Edge;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+
+fn main() {
+    let engine = build(&[
+        ("parse", &["input"]),
+        ("check", &["parse"]),
+        ("emit", &["check"]),
+        ("format", &["parse"]),
+    ]);
+
+    let changed = build(&[
+        ("parse", &["input"]),
+        ("check", &["input"]),
+        ("lint", &["input"]),
+        ("emit", &["check", "lint"]),
+    ]);
+
+    assert!(engine.diff(&engine).is_empty());
+
+    let diff = engine.diff(&changed);
+    assert_eq!(diff.added_nodes(), ["lint"]);
+    assert_eq!(diff.removed_nodes(), ["format"]);
+    assert_eq!(
+        set(diff.added_edges()),
+        HashSet::from([("input", "check"), ("input", "lint"), ("lint", "emit")])
+    );
+    assert_eq!(
+        set(diff.removed_edges()),
+        HashSet::from([("parse", "check"), ("parse", "format")])
+    );
+    assert_eq!(diff.changed_ancestors(), ["check", "emit"]);
+
+    let text = diff.to_string();
+    assert!(text.contains("+ lint\n"));
+    assert!(text.contains("- format\n"));
+    assert!(text.contains("- parse -> check\n"));
+    assert!(text.contains("~ emit\n"));
+
+    let dot = diff.to_dot();
+    assert!(dot.starts_with("digraph {\n") && dot.ends_with('}'));
+    assert!(dot.contains("    \"lint\" [color=green];\n"));
+    assert!(dot.contains("    \"format\" [color=red, style=dashed];\n"));
+    assert!(dot.contains("    \"check\" [color=orange];\n"));
+    assert!(dot.contains("    \"parse\";\n"));
+    assert!(dot.contains("    \"lint\" -> \"emit\" [color=green];\n"));
+    assert!(dot.contains("    \"parse\" -> \"check\" [color=red, style=dashed];\n"));
+    assert!(dot.contains("    \"input\" -> \"parse\";\n"));
+
+    // Turning a data dependency into an ordering-only one keeps the edge but
+    // changes its kind.
+    let ordered = Engine::builder();
+    ordered
+        .add_input("input".into())
+        .add_task(Step::from("parse", &["input"]))
+        .add_task(Step::from("check", &[]).after(&["parse"]))
+        .add_task(Step::from("emit", &["check"]))
+        .add_task(Step::from("format", &["parse"]));
+
+    let diff = engine.diff(&ordered.build().unwrap());
+    assert!(!diff.is_empty());
+    assert!(diff.added_edges().is_empty() && diff.removed_edges().is_empty());
+    assert_eq!(
+        set(diff.changed_edges()),
+        HashSet::from([("parse", "check")])
+    );
+    assert!(diff.changed_ancestors().is_empty());
+    assert_eq!(diff.to_string(), "~ parse -> check\n");
+    assert!(
+        diff.to_dot()
+            .contains("    \"parse\" -> \"check\" [color=orange];\n")
+    );
+}
+
+fn build(tasks: &[(&str, &[&str])]) -> Engine<'static, String, ()> {
+    let builder = Engine::builder();
+    builder.add_input("input".into());
+    for (id, dependencies) in tasks {
+        builder.add_task(Step::from(id, dependencies));
+    }
+
+    builder.build().unwrap()
+}
+
+fn set(edges: &[Edge<String>]) -> HashSet<(&str, &str)> {
+    edges
+        .iter()
+        .map(|Edge { from, to }| (from.as_str(), to.as_str()))
+        .collect()
+}
+
+struct Step {
+    id: String,
+    dependencies: Vec<String>,
+    after: Vec<String>,
+}
+
+impl Step {
+    fn from(id: &str, dependencies: &[&str]) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.iter().map(|&id| id.into()).collect(),
+            after: Vec::new(),
+        }
+    }
+
+    fn after(mut self, after: &[&str]) -> Self {
+        self.after = after.iter().map(|&id| id.into()).collect();
+        self
+    }
+}
+
+impl Task<String, ()> for Step {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn after(&self) -> Vec<String> {
+        self.after.clone()
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, ()>>, _: Scope<String, ()>) -> Option<()> {
+        None
+    }
+}
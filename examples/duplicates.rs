@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::EngineBuilder;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+
+fn main() {
+    // A second `add_task` with the same id fails the build.
+    let builder = Engine::builder();
+    builder
+        .add_task(Constant::from("a", 1))
+        .add_task(Constant::from("a", 2))
+        .add_task(Constant::from("b", 3))
+        .add_task(Constant::from("b", 4));
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(
+        err.duplicate_tasks(),
+        Some(["a".to_string(), "b".to_string()].as_slice())
+    );
+    assert_eq!(format!("{err}"), r#"duplicate task ids: ["a", "b"]"#);
+
+    // `try_add_task` rejects a duplicate right away, keeps the first task and
+    // hands the rejected one back.
+    let builder = Engine::builder();
+    builder.add_task(Constant::from("a", 1));
+
+    let err = builder.try_add_task(Constant::from("a", 2)).err().unwrap();
+    assert_eq!(err.id(), "a");
+    assert_eq!(format!("{err}"), r#"duplicate task id: "a""#);
+
+    let rejected = err.into_task();
+    assert_eq!(rejected.value, 2);
+    assert!(builder.try_add_task(Constant::from("b", 3)).is_ok());
+    assert_eq!(outputs(builder), [("a", 1), ("b", 3)]);
+
+    // Replacing a task also clears its duplicates.
+    let builder = Engine::builder();
+    builder
+        .add_task(Constant::from("a", 1))
+        .add_task(Constant::from("a", 2))
+        .replace_task(Constant::from("a", 5));
+
+    assert_eq!(outputs(builder), [("a", 5)]);
+
+    // So does removing it.
+    let builder = Engine::builder();
+    builder
+        .add_task(Constant::from("a", 1))
+        .add_task(Constant::from("a", 2))
+        .add_task(Constant::from("b", 3))
+        .remove_task_by_id(&"a".into());
+
+    assert_eq!(outputs(builder), [("b", 3)]);
+}
+
+fn outputs(builder: EngineBuilder<String, u64>) -> Vec<(&'static str, u64)> {
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    executor::block_on(engine.run(context.clone()));
+
+    let mut outputs: Vec<_> = ["a", "b"]
+        .into_iter()
+        .flat_map(|id| {
+            let output = executor::block_on(context.get(&id.into())?)?;
+            Some((id, output))
+        })
+        .collect();
+
+    outputs.sort();
+    outputs
+}
+
+struct Constant {
+    id: String,
+    value: u64,
+}
+
+impl Constant {
+    fn from(id: &str, value: u64) -> Self {
+        Self {
+            id: id.into(),
+            value,
+        }
+    }
+}
+
+impl Task<String, u64> for Constant {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, u64>>, _: Scope<String, u64>) -> Option<u64> {
+        Some(self.value)
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::Instant;
+
+use dag_flow::dag::Dag;
+use dag_flow::dag::DagBuilder;
+use dag_flow::dag::Edge;
+
+fn main() {
+    let mut builder = Dag::builder();
+    builder
+        .try_add_edge(Edge::new("a", "b"))
+        .unwrap()
+        .try_add_edge(Edge::new("b", "c"))
+        .unwrap();
+
+    let err = builder.try_add_edge(Edge::new("c", "a")).err().unwrap();
+    assert_eq!(err.cycle(), ["c", "a", "b"]);
+    assert!(!builder.contains_edge(&"c", &"a"));
+    assert!(builder.try_add_edge(Edge::new("a", "a")).is_err());
+
+    // Edges added without checks may break the order, which is then rebuilt.
+    builder.add_edge(Edge::new("d", "a"));
+    builder.try_add_edge(Edge::new("c", "e")).unwrap();
+    assert!(builder.try_add_edge(Edge::new("e", "d")).is_err());
+
+    let mut random = Random(7);
+    let mut builder = Dag::builder();
+    let mut edges: HashMap<_, HashSet<_>> = HashMap::new();
+
+    for _ in 0..4000 {
+        let from = random.next() % 200;
+        let to = random.next() % 200;
+        let closes_cycle = from == to || reaches(&edges, to, from);
+
+        match builder.try_add_edge(Edge::new(from, to)) {
+            Ok(_) => {
+                assert!(!closes_cycle);
+                edges.entry(from).or_default().insert(to);
+            }
+            Err(err) => {
+                assert!(closes_cycle);
+                let cycle = err.cycle();
+                assert_eq!((cycle[0], cycle[1 % cycle.len()]), (from, to));
+
+                for (i, node) in cycle.iter().enumerate().skip(1) {
+                    assert!(edges[node].contains(&cycle[(i + 1) % cycle.len()]));
+                }
+            }
+        }
+    }
+
+    check(builder);
+
+    let nodes = 20_000;
+    let mut builder = Dag::builder();
+
+    for _ in 0..nodes * 2 {
+        let (from, to) = (random.next() % nodes, random.next() % nodes);
+        if from < to {
+            builder.try_add_edge(Edge::new(from, to)).unwrap();
+        }
+    }
+
+    for _ in 0..100 {
+        let (from, to) = (random.next() % nodes, random.next() % nodes);
+        let _ = builder.try_add_edge(Edge::new(from, to));
+    }
+
+    check(builder);
+
+    // Chains grown at either end never need reordering, however long.
+    let nodes = 50_000;
+    let now = Instant::now();
+    let mut prepended = Dag::builder();
+    let mut appended = Dag::builder();
+
+    for i in (0..nodes).rev() {
+        prepended.try_add_edge(Edge::new(i, i + 1)).unwrap();
+    }
+
+    for i in 0..nodes {
+        appended.try_add_edge(Edge::new(i, i + 1)).unwrap();
+    }
+
+    assert!(prepended.try_add_edge(Edge::new(nodes, 0)).is_err());
+    assert!(now.elapsed() < Duration::from_secs(10));
+    check(prepended);
+    check(appended);
+}
+
+fn check(builder: DagBuilder<u64>) {
+    let dag = builder.build().unwrap();
+    let order = dag.topological_order();
+    let positions: HashMap<_, _> = order
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node, i))
+        .collect();
+
+    assert!(
+        dag.edges()
+            .all(|Edge { from, to }| positions[from] < positions[to])
+    );
+}
+
+fn reaches(edges: &HashMap<u64, HashSet<u64>>, from: u64, to: u64) -> bool {
+    let mut stack = vec![from];
+    let mut visited = HashSet::from([from]);
+
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+
+        for &next in edges.get(&node).into_iter().flatten() {
+            if visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    false
+}
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
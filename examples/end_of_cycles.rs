@@ -3,6 +3,7 @@ use std::error::Error;
 
 use dag_flow::engine::Engine;
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
 
 fn main() {
@@ -41,7 +42,7 @@ impl Task<usize, ()> for Void {
         self.dependencies.clone()
     }
 
-    async fn run(&self, _: HashMap<usize, Input<'_, ()>>) -> Option<()> {
+    async fn run(&self, _: HashMap<usize, Input<'_, ()>>, _: Scope<usize, ()>) -> Option<()> {
         None
     }
 }
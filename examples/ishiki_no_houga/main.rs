@@ -1,11 +1,8 @@
-use std::collections::HashMap;
 use std::time::Instant;
 
 use dag_flow::context::Context;
 use dag_flow::engine::Engine;
-use futures::StreamExt;
 use futures::executor;
-use futures::stream::FuturesUnordered;
 
 mod tasks;
 use tasks::daikichi_yama::DaikichiYama;
@@ -33,21 +30,16 @@ fn main() {
     let kousaka_reina = KousakaReina::id();
     let daikichi_yama = DaikichiYama::id();
 
-    let data: HashMap<_, _> = executor::block_on(
-        vec![&oumae_kumiko, &kousaka_reina, &daikichi_yama]
-            .into_iter()
-            .map(|id| {
-                let data = context.get(id).unwrap();
-                async move { (id, data.await.unwrap()) }
-            })
-            .collect::<FuturesUnordered<_>>()
-            .collect(),
-    );
+    let data = executor::block_on(context.snapshot());
 
     assert_eq!(
         format!(
             "{}",
-            data[&oumae_kumiko].clone().downcast::<Euphonium>().unwrap()
+            data[&oumae_kumiko]
+                .clone()
+                .unwrap()
+                .downcast::<Euphonium>()
+                .unwrap()
         ),
         "Euphonium"
     );
@@ -55,7 +47,11 @@ fn main() {
     assert_eq!(
         format!(
             "{}",
-            data[&kousaka_reina].clone().downcast::<Trumpet>().unwrap()
+            data[&kousaka_reina]
+                .clone()
+                .unwrap()
+                .downcast::<Trumpet>()
+                .unwrap()
         ),
         "Trumpet"
     );
@@ -65,6 +61,7 @@ fn main() {
             "{}",
             data[&daikichi_yama]
                 .clone()
+                .unwrap()
                 .downcast::<Observatory>()
                 .unwrap()
         ),
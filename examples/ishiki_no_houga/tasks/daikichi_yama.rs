@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
 
 use super::kousaka_reina::KousakaReina;
@@ -41,6 +42,7 @@ impl Task<String, Arc<dyn Any + Send + Sync>> for DaikichiYama {
     async fn run(
         &self,
         inputs: HashMap<String, Input<'_, Arc<dyn Any + Send + Sync>>>,
+        _: Scope<String, Arc<dyn Any + Send + Sync>>,
     ) -> Option<Arc<dyn Any + Send + Sync>> {
         let _euphonium: Arc<Euphonium> = inputs[&OumaeKumiko::id()]
             .clone()
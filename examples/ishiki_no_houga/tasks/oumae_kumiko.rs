@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
 use futures_timer::Delay;
 
@@ -34,6 +35,7 @@ impl Task<String, Arc<dyn Any + Send + Sync>> for OumaeKumiko {
     async fn run(
         &self,
         _: HashMap<String, Input<'_, Arc<dyn Any + Send + Sync>>>,
+        _: Scope<String, Arc<dyn Any + Send + Sync>>,
     ) -> Option<Arc<dyn Any + Send + Sync>> {
         Delay::new(Duration::from_secs(1)).await;
         Some(Arc::new(Euphonium::new()))
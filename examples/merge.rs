@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Conflict;
+use dag_flow::engine::Engine;
+use dag_flow::engine::EngineBuilder;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+
+fn main() {
+    // `Conflict::Error` leaves the builder untouched when any id is taken.
+    let builder = fragment(1);
+    let err = builder.merge(count(10), Conflict::Error).err().unwrap();
+    assert_eq!(
+        err.duplicate_tasks(),
+        Some(["count".to_string()].as_slice())
+    );
+    assert_eq!(outputs(builder, &["count", "double"]), [Some(1), Some(2)]);
+
+    // `Conflict::Replace` takes the tasks of the merged builder.
+    let builder = fragment(1);
+    builder.merge(count(10), Conflict::Replace).unwrap();
+    assert_eq!(outputs(builder, &["count", "double"]), [Some(10), Some(20)]);
+
+    // `Conflict::Keep` keeps the tasks already there.
+    let builder = fragment(1);
+    builder.merge(count(10), Conflict::Keep).unwrap();
+    assert_eq!(outputs(builder, &["count", "double"]), [Some(1), Some(2)]);
+
+    // Namespaced fragments merge without conflicts. Dependencies within a
+    // fragment follow it into the namespace, while those on other fragments
+    // keep their ids.
+    let builder = fragment(1).namespace(|id| format!("left/{id}"));
+    let right = fragment(10);
+    right.add_task(Sum::from("total", 0, vec!["double", "left/double"]));
+
+    builder
+        .merge(right.namespace(|id| format!("right/{id}")), Conflict::Error)
+        .unwrap();
+
+    assert_eq!(
+        outputs(builder, &["left/double", "right/double", "right/total"]),
+        [Some(2), Some(20), Some(22)]
+    );
+
+    // Ids that collide once namespaced are reported like any duplicate.
+    let builder = Engine::builder();
+    builder
+        .add_task(Sum::from("Count", 1, Vec::new()))
+        .add_task(Sum::from("count", 2, Vec::new()));
+
+    let err = builder
+        .namespace(|id| id.to_lowercase())
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.duplicate_tasks(),
+        Some(["count".to_string()].as_slice())
+    );
+}
+
+fn count(count: u64) -> EngineBuilder<'static, String, u64> {
+    let builder = Engine::builder();
+    builder.add_task(Sum::from("count", count, Vec::new()));
+    builder
+}
+
+fn fragment(count: u64) -> EngineBuilder<'static, String, u64> {
+    let builder = self::count(count);
+    builder.add_task(Sum::from("double", 0, vec!["count", "count"]));
+    builder
+}
+
+fn outputs(builder: EngineBuilder<String, u64>, ids: &[&str]) -> Vec<Option<u64>> {
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    executor::block_on(engine.run(context.clone()));
+
+    ids.iter()
+        .map(|id| executor::block_on(context.get(&id.to_string()).unwrap()))
+        .collect()
+}
+
+struct Sum {
+    id: String,
+    value: u64,
+    dependencies: Vec<String>,
+}
+
+impl Sum {
+    fn from(id: &str, value: u64, dependencies: Vec<&str>) -> Self {
+        Self {
+            id: id.into(),
+            value,
+            dependencies: dependencies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Task<String, u64> for Sum {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let mut sum = self.value;
+        for dependency in &self.dependencies {
+            sum += inputs[dependency].clone().await?;
+        }
+
+        Some(sum)
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use dag_flow::dag::Dag;
+use dag_flow::dag::Edge;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+
+fn main() {
+    let mut builder = Dag::builder();
+    for (from, to) in [("a", "b"), ("a", "c"), ("a", "d"), ("b", "e"), ("c", "e")] {
+        builder.add_edge(Edge::new(from, to));
+    }
+
+    let dag = builder.build().unwrap();
+    assert_eq!(dag.depth(), 3);
+    assert_eq!(dag.width(), 3);
+
+    let layers = dag.layers();
+    assert_eq!((layers["a"], layers["d"], layers["e"]), (0, 1, 2));
+
+    let path = dag.longest_path(|&node| if node == "c" { 5 } else { 1 });
+    assert_eq!(path.cost, 7);
+    assert_eq!(path.nodes, ["a", "c", "e"]);
+
+    let empty = Dag::<u32>::builder().build().unwrap();
+    assert_eq!((empty.depth(), empty.width()), (0, 0));
+    assert!(empty.longest_path(|_| 1).nodes.is_empty());
+
+    let builder = Engine::builder();
+    builder
+        .add_input("source".into())
+        .add_task(Step::from("fetch", 10, &["source"]))
+        .add_task(Step::from("parse", 2, &["fetch"]))
+        .add_task(Step::from("index", 1, &["fetch"]))
+        .add_task(Step::from("lint", 1, &["source"]))
+        .add_task(Step::from("report", 1, &["parse", "index", "lint"]));
+
+    let engine = builder.build().unwrap();
+    let metrics = engine.metrics();
+
+    assert_eq!(metrics.depth, 3);
+    assert_eq!(metrics.width, 3);
+    assert_eq!(metrics.layers.len(), 5);
+    assert_eq!(metrics.layers["report"], 2);
+    assert_eq!(metrics.critical_path.cost, 13);
+    assert_eq!(metrics.critical_path.nodes, ["fetch", "parse", "report"]);
+    assert_eq!(engine.dag().len(), 6);
+
+    // Building stays cheap for long chains, which only pay for the metrics
+    // once asked.
+    let builder = Engine::builder();
+    builder.add_task(Step::from("0", 1, &[]));
+    for index in 1..CHAIN {
+        let dependency = (index - 1).to_string();
+        builder.add_task(Step::from(&index.to_string(), 1, &[&dependency]));
+    }
+
+    let now = Instant::now();
+    let engine = builder.build().unwrap();
+    assert!(now.elapsed() < Duration::from_secs(1));
+
+    let metrics = engine.metrics();
+    assert_eq!((metrics.depth, metrics.width), (CHAIN, 1));
+    assert_eq!(metrics.critical_path.cost, CHAIN as u64);
+
+    let mut random = Random(3);
+    for _ in 0..200 {
+        let nodes = 1 + random.next() % 10;
+        let mut builder = Dag::builder();
+        for node in 0..nodes {
+            builder.add_node(node);
+        }
+
+        for _ in 0..random.next() % 20 {
+            let (from, to) = (random.next() % nodes, random.next() % nodes);
+            if from < to {
+                builder.add_edge(Edge::new(from, to));
+            }
+        }
+
+        let dag = builder.build().unwrap();
+        assert_eq!(dag.width(), max_antichain(&dag));
+    }
+
+    // Two chains where every node of the first also reaches the next node of
+    // the second, listed first so that the initial matching leaves every node
+    // of the second chain to an augmenting search.
+    let mut builder = Dag::builder();
+    for index in 1..CHAIN {
+        builder
+            .add_edge(Edge::new((0, index - 1), (1, index)))
+            .add_edge(Edge::new((0, index - 1), (0, index)))
+            .add_edge(Edge::new((1, index - 1), (1, index)));
+    }
+
+    assert_eq!(builder.build().unwrap().width(), 2);
+}
+
+const CHAIN: usize = 5000;
+
+fn max_antichain(dag: &Dag<u64>) -> usize {
+    let nodes: Vec<_> = dag.nodes().copied().collect();
+    let reaches = |from: u64, to: u64| {
+        let mut stack = vec![from];
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+
+            stack.extend(dag.graph()[&node].out_neighbors.iter().copied());
+        }
+
+        false
+    };
+
+    (0..1u32 << nodes.len())
+        .filter(|set| {
+            let members: Vec<_> = (0..nodes.len()).filter(|i| set >> i & 1 == 1).collect();
+            members.iter().all(|&a| {
+                members
+                    .iter()
+                    .all(|&b| a == b || !reaches(nodes[a], nodes[b]))
+            })
+        })
+        .map(|set| set.count_ones() as usize)
+        .max()
+        .unwrap()
+}
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+struct Step {
+    id: String,
+    cost: u64,
+    dependencies: Vec<String>,
+}
+
+impl Step {
+    fn from(id: &str, cost: u64, dependencies: &[&str]) -> Self {
+        Self {
+            id: id.into(),
+            cost,
+            dependencies: dependencies.iter().map(|&id| id.into()).collect(),
+        }
+    }
+}
+
+impl Task<String, ()> for Step {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, ()>>, _: Scope<String, ()>) -> Option<()> {
+        None
+    }
+}
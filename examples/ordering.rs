@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use dag_flow::context::Context;
+use dag_flow::engine::EdgeKind;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+use futures::future;
+use futures_timer::Delay;
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+fn main() {
+    let log = Log::default();
+
+    let builder = Engine::builder();
+    builder
+        .add_task(Step::new("create", &log).delay(20))
+        .add_task(Step::new("write", &log).after(&["create"]))
+        .add_task(
+            Step::new("read", &log)
+                .dependencies(&["write"])
+                .after(&["write"]),
+        );
+
+    let engine = builder.build().unwrap();
+    let dag = engine.dag();
+    assert_eq!(
+        dag.edge_value(&"create".into(), &"write".into()),
+        Some(&EdgeKind::Ordering)
+    );
+    assert_eq!(
+        dag.edge_value(&"write".into(), &"read".into()),
+        Some(&EdgeKind::Data)
+    );
+
+    let context = Context::new();
+    executor::block_on(engine.run(context.clone()));
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["create", "write with 0 inputs", "read with 1 inputs"]
+    );
+    assert_eq!(
+        executor::block_on(context.get(&"read".into()).unwrap()),
+        Some(1)
+    );
+}
+
+struct Step {
+    id: &'static str,
+    dependencies: Vec<String>,
+    after: Vec<String>,
+    delay: u64,
+    log: Log,
+}
+
+impl Step {
+    fn new(id: &'static str, log: &Log) -> Self {
+        Self {
+            id,
+            dependencies: Vec::new(),
+            after: Vec::new(),
+            delay: 0,
+            log: log.clone(),
+        }
+    }
+
+    fn dependencies(mut self, ids: &[&str]) -> Self {
+        self.dependencies = ids.iter().map(|&id| id.into()).collect();
+        self
+    }
+
+    fn after(mut self, ids: &[&str]) -> Self {
+        self.after = ids.iter().map(|&id| id.into()).collect();
+        self
+    }
+
+    fn delay(mut self, millis: u64) -> Self {
+        self.delay = millis;
+        self
+    }
+}
+
+impl Task<String, usize> for Step {
+    fn id(&self) -> String {
+        self.id.into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn after(&self) -> Vec<String> {
+        self.after.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, usize>>,
+        _: Scope<String, usize>,
+    ) -> Option<usize> {
+        future::join_all(inputs.values().cloned()).await;
+        Delay::new(Duration::from_millis(self.delay)).await;
+
+        let event = match inputs.len() {
+            0 if self.after.is_empty() => self.id,
+            0 => "write with 0 inputs",
+            _ => "read with 1 inputs",
+        };
+
+        self.log.lock().unwrap().push(event);
+        Some(inputs.len())
+    }
+}
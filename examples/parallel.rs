@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::ThreadId;
+use std::time::Duration;
+use std::time::Instant;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::ThreadPool;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+use futures::future;
+
+const WORK: Duration = Duration::from_millis(50);
+const TASKS: usize = 4;
+
+static THREADS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+static RUNS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn main() {
+    let builder = Engine::builder();
+    for id in 0..TASKS {
+        builder.add_task(Busy::new(&format!("busy-{id}"), &[], true));
+    }
+
+    builder
+        .add_task(Busy::new("lazy", &[], false))
+        .add_task(Busy::new("unused", &[], false))
+        .add_task(Busy::new("sum", &["busy-0", "busy-1", "lazy"], true))
+        .spawner(ThreadPool::new(TASKS + 2));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+
+    let now = Instant::now();
+    executor::block_on(engine.run_parallel(context.clone()));
+    assert!(now.elapsed() < WORK * 3);
+
+    assert_eq!(
+        executor::block_on(context.get(&"sum".into()).unwrap()),
+        Some(3)
+    );
+
+    let runs = RUNS.lock().unwrap();
+    assert!(runs.contains(&"lazy".into()));
+    assert!(!runs.contains(&"unused".into()));
+
+    let threads: HashSet<_> = THREADS.lock().unwrap().iter().copied().collect();
+    assert!(threads.len() > 1);
+    assert!(!threads.contains(&thread::current().id()));
+}
+
+struct Busy {
+    id: String,
+    dependencies: Vec<String>,
+    is_auto: bool,
+}
+
+impl Busy {
+    fn new(id: &str, dependencies: &[&str], is_auto: bool) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.iter().map(|&id| id.into()).collect(),
+            is_auto,
+        }
+    }
+}
+
+impl Task<String, u64> for Busy {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_auto(&self) -> bool {
+        self.is_auto
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let outputs = future::join_all(inputs.values().cloned()).await;
+        let sum = outputs.into_iter().sum::<Option<u64>>()?;
+
+        RUNS.lock().unwrap().push(self.id.clone());
+        THREADS.lock().unwrap().push(thread::current().id());
+        thread::sleep(WORK);
+
+        Some(sum.max(1))
+    }
+}
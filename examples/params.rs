@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use dag_flow::context::Context;
+use dag_flow::engine::CacheStatus;
+use dag_flow::engine::Engine;
+use dag_flow::engine::LruCache;
+use dag_flow::engine::SubEngine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+use futures::future;
+
+struct Request {
+    user_id: u64,
+    days: u64,
+}
+
+fn main() {
+    let builder = Engine::builder();
+    builder.add_task(Days);
+
+    let sub_engine = SubEngine::new("days".into(), builder.build().unwrap()).output("days".into());
+
+    let builder = Engine::builder();
+    builder
+        .add_task(UserId)
+        .add_task(sub_engine)
+        .cache(LruCache::new(8));
+
+    let engine = builder.build().unwrap();
+    let contexts: Vec<_> = [(1, 7), (2, 30)]
+        .into_iter()
+        .map(|(user_id, days)| Context::new().with_params(Request { user_id, days }))
+        .collect();
+
+    // Params are not part of the cache key, so runs with params skip it.
+    let reports = executor::block_on(future::join_all(
+        contexts.iter().map(|context| engine.run(context.clone())),
+    ));
+
+    for report in &reports {
+        assert_eq!(report.tasks[&"user-id".to_string()].cache, None);
+    }
+
+    for context in &contexts {
+        let request = context.params::<Request>().unwrap();
+        let outputs = executor::block_on(async {
+            (
+                context.get(&"user-id".into()).unwrap().await,
+                context.get(&"days".into()).unwrap().await,
+            )
+        });
+
+        assert_eq!(outputs, (Some(request.user_id), Some(request.days)));
+    }
+
+    let context = Context::new();
+    let report = executor::block_on(engine.run(context.clone()));
+    assert_eq!(
+        executor::block_on(context.get(&"user-id".into()).unwrap()),
+        None
+    );
+    assert_eq!(
+        report.tasks[&"user-id".to_string()].cache,
+        Some(CacheStatus::Miss)
+    );
+}
+
+struct UserId;
+
+impl Task<String, u64> for UserId {
+    fn id(&self) -> String {
+        "user-id".into()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        Some(scope.params::<Request>()?.user_id)
+    }
+}
+
+struct Days;
+
+impl Task<String, u64> for Days {
+    fn id(&self) -> String {
+        "days".into()
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        Some(scope.params::<Request>()?.days)
+    }
+}
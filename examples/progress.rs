@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::Observer;
+use dag_flow::engine::Progress;
+use dag_flow::engine::TaskProgress;
+use dag_flow::engine::TaskReport;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+use futures::future;
+use futures::future::Either;
+use futures_timer::Delay;
+
+const CHUNKS: u64 = 5;
+
+fn main() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let builder = Engine::builder();
+    builder
+        .add_task(Download)
+        .add_task(Index)
+        .observer(Events(events.clone()));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    let progress = Progress::new();
+
+    let fractions = executor::block_on(async {
+        let mut fractions = Vec::new();
+        let mut run = Box::pin(engine.run_with_progress(context.clone(), &progress));
+
+        loop {
+            match future::select(run, Delay::new(Duration::from_millis(5))).await {
+                Either::Left(_) => break,
+                Either::Right((_, pending)) => {
+                    fractions.push(progress.fraction());
+                    run = pending;
+                }
+            }
+        }
+
+        fractions
+    });
+
+    assert!(fractions.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(
+        fractions
+            .iter()
+            .any(|&fraction| 0.0 < fraction && fraction < 1.0)
+    );
+    assert_eq!((progress.done(), progress.total()), (4, 4));
+    assert_eq!(progress.fraction(), 1.0);
+
+    let events = events.lock().unwrap();
+    let position = |event| events.iter().position(|e| e == event).unwrap();
+    assert!(position("start download") < position("finish download"));
+    assert!(position("start index") < position("finish index"));
+    assert_eq!(events.last().unwrap(), "finish index");
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| event.starts_with("progress download"))
+            .count() as u64,
+        CHUNKS
+    );
+}
+
+struct Events(Arc<Mutex<Vec<String>>>);
+
+impl Observer<String> for Events {
+    fn on_start(&self, _: u64, id: &String) {
+        self.0.lock().unwrap().push(format!("start {id}"));
+    }
+
+    fn on_progress(&self, _: u64, id: &String, progress: &TaskProgress) {
+        self.0.lock().unwrap().push(format!(
+            "progress {id} {}/{} {}",
+            progress.done, progress.total, progress.message
+        ));
+    }
+
+    fn on_finish(&self, _: u64, id: &String, _: &TaskReport) {
+        self.0.lock().unwrap().push(format!("finish {id}"));
+    }
+}
+
+struct Download;
+
+impl Task<String, u64> for Download {
+    fn id(&self) -> String {
+        "download".into()
+    }
+
+    fn cost(&self) -> u64 {
+        3
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        for chunk in 1..=CHUNKS {
+            Delay::new(Duration::from_millis(10)).await;
+            scope.progress(chunk, CHUNKS, format!("chunk {chunk}"));
+        }
+
+        Some(CHUNKS)
+    }
+}
+
+struct Index;
+
+impl Task<String, u64> for Index {
+    fn id(&self) -> String {
+        "index".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["download".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let chunks = inputs["download"].clone().await?;
+        Delay::new(Duration::from_millis(10)).await;
+
+        Some(chunks * 2)
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::Report;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::executor;
+use futures::future;
+
+type Runs = Arc<Mutex<Vec<String>>>;
+
+fn main() {
+    let runs = Runs::default();
+
+    // parity <- x, label <- parity, double <- y, total <- label + double
+    let builder = Engine::builder();
+    builder
+        .add_input("x".into())
+        .add_input("y".into())
+        .add_task(Map::from("parity", vec!["x"], |x| x % 2, runs.clone()))
+        .add_task(Map::from("label", vec!["parity"], |p| p * 10, runs.clone()))
+        .add_task(Map::from("double", vec!["y"], |y| y * 2, runs.clone()))
+        .add_task(Map::from(
+            "total",
+            vec!["label", "double"],
+            |s| s,
+            runs.clone(),
+        ));
+
+    let engine = builder.build().unwrap();
+
+    let previous = inputs(2, 3);
+    executor::block_on(engine.run(previous.clone()));
+    assert_eq!(ran(&runs), ["double", "label", "parity", "total"]);
+    assert_eq!(output(&previous, "total"), Some(6));
+
+    // Only `x` and its dependents are dirty, so `double` is reused.
+    let context = inputs(4, 3);
+    let report = executor::block_on(engine.recompute(context.clone(), &previous, &["x".into()]));
+    assert_eq!(ran(&runs), ["label", "parity", "total"]);
+    assert_eq!(reported(&report), ["label", "parity", "total"]);
+    assert_eq!(output(&context, "double"), Some(6));
+    assert_eq!(output(&context, "total"), Some(6));
+
+    // With a cutoff, `parity` runs again but comes out the same, so `label`
+    // and in turn `total` reuse their previous outputs.
+    let context = inputs(4, 3);
+    let report =
+        executor::block_on(engine.recompute_with_cutoff(context.clone(), &previous, &["x".into()]));
+
+    assert_eq!(ran(&runs), ["parity"]);
+    assert_eq!(reported(&report), ["parity"]);
+    assert_eq!(output(&context, "label"), Some(0));
+    assert_eq!(output(&context, "total"), Some(6));
+
+    // A changed output still propagates past the cutoff.
+    let context = inputs(5, 3);
+    executor::block_on(engine.recompute_with_cutoff(context.clone(), &previous, &["x".into()]));
+    assert_eq!(ran(&runs), ["label", "parity", "total"]);
+    assert_eq!(output(&context, "total"), Some(16));
+
+    // `fill` is ordered after `create`, which recreates the table it writes
+    // to. Even though `create` comes out the same, `fill` has to run again.
+    let builder = Engine::builder();
+    builder
+        .add_input("x".into())
+        .add_task(Map::from("create", vec!["x"], |_| 0, runs.clone()))
+        .add_task(Map::from("fill", Vec::new(), |_| 1, runs.clone()).after(&["create"]));
+
+    let engine = builder.build().unwrap();
+    let previous = inputs(2, 3);
+    executor::block_on(engine.run(previous.clone()));
+    assert_eq!(ran(&runs), ["create", "fill"]);
+
+    let context = inputs(4, 3);
+    executor::block_on(engine.recompute_with_cutoff(context.clone(), &previous, &["x".into()]));
+    assert_eq!(ran(&runs), ["create", "fill"]);
+}
+
+fn inputs(x: u64, y: u64) -> Context<'static, String, Option<u64>> {
+    let context = Context::new();
+    context.set("x".into(), future::ready(Some(x)).boxed().shared());
+    context.set("y".into(), future::ready(Some(y)).boxed().shared());
+    context
+}
+
+fn output(context: &Context<String, Option<u64>>, id: &str) -> Option<u64> {
+    executor::block_on(context.get(&id.into()).unwrap())
+}
+
+// The tasks that ran since the last call, sorted.
+fn ran(runs: &Runs) -> Vec<String> {
+    let mut runs = mem::take(&mut *runs.lock().unwrap());
+    runs.sort();
+    runs
+}
+
+fn reported(report: &Report<String>) -> Vec<&str> {
+    let mut ids: Vec<_> = report.tasks.keys().map(String::as_str).collect();
+    ids.sort();
+    ids
+}
+
+struct Map {
+    id: String,
+    dependencies: Vec<String>,
+    after: Vec<String>,
+    f: fn(u64) -> u64,
+    runs: Runs,
+}
+
+impl Map {
+    fn from(id: &str, dependencies: Vec<&str>, f: fn(u64) -> u64, runs: Runs) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.into_iter().map(Into::into).collect(),
+            after: Vec::new(),
+            f,
+            runs,
+        }
+    }
+
+    fn after(mut self, after: &[&str]) -> Self {
+        self.after = after.iter().map(|&id| id.into()).collect();
+        self
+    }
+}
+
+impl Task<String, u64> for Map {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn after(&self) -> Vec<String> {
+        self.after.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        self.runs.lock().unwrap().push(self.id.clone());
+
+        let mut sum = 0;
+        for dependency in &self.dependencies {
+            sum += inputs[dependency].clone().await?;
+        }
+
+        Some((self.f)(sum))
+    }
+}
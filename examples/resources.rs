@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::EngineBuilder;
+use dag_flow::engine::ResourceId;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+
+struct Config {
+    base: u64,
+}
+
+#[derive(Default)]
+struct Client {
+    requests: AtomicU64,
+}
+
+impl Client {
+    fn get(&self, key: u64) -> u64 {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        key * 10
+    }
+}
+
+fn main() {
+    let builder = fetch_builder();
+    builder.resource(Config { base: 100 });
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        format!("missing resources: [{:?}]", ResourceId::of::<Client>())
+    );
+
+    let builder = fetch_builder();
+    builder
+        .resource(Config { base: 100 })
+        .resource(Client::default());
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+
+    executor::block_on(engine.run(context.clone()));
+
+    let outputs = executor::block_on(async {
+        (
+            context.get(&"a".into()).unwrap().await,
+            context.get(&"b".into()).unwrap().await,
+        )
+    });
+
+    assert_eq!(outputs, (Some(110), Some(120)));
+}
+
+fn fetch_builder() -> EngineBuilder<'static, String, u64> {
+    let builder = Engine::builder();
+    builder.add_task(Fetch("a", 1)).add_task(Fetch("b", 2));
+    builder
+}
+
+struct Fetch(&'static str, u64);
+
+impl Task<String, u64> for Fetch {
+    fn id(&self) -> String {
+        self.0.into()
+    }
+
+    fn resources(&self) -> Vec<ResourceId> {
+        vec![ResourceId::of::<Client>()]
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        let client = scope.resource::<Client>()?;
+        let base = scope.resource::<Config>().map_or(0, |config| config.base);
+
+        Some(base + client.get(self.1))
+    }
+}
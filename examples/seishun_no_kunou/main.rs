@@ -1,11 +1,8 @@
-use std::collections::HashMap;
 use std::time::Instant;
 
 use dag_flow::context::Context;
 use dag_flow::engine::Engine;
-use futures::StreamExt;
 use futures::executor;
-use futures::stream::FuturesUnordered;
 
 mod tasks;
 use tasks::oumae_kumiko::OumaeKumiko;
@@ -27,24 +24,18 @@ fn main() {
     let oumae_kumiko = OumaeKumiko::id();
     let uji_bashi = UjiBashi::id();
 
-    let data: HashMap<_, _> = executor::block_on(
-        vec![&oumae_kumiko, &uji_bashi]
-            .into_iter()
-            .map(|id| {
-                let data = context.get(id).unwrap();
-                async move { (id, data.await.unwrap()) }
-            })
-            .collect::<FuturesUnordered<_>>()
-            .collect(),
-    );
+    let data = executor::block_on(context.snapshot());
 
     assert_eq!(
-        format!("{}", data[&oumae_kumiko].clone().oumae_kumiko().unwrap()),
+        format!(
+            "{}",
+            data[&oumae_kumiko].clone().unwrap().oumae_kumiko().unwrap()
+        ),
         "Umaku Naritai"
     );
 
     assert_eq!(
-        format!("{}", data[&uji_bashi].clone().uji_bashi().unwrap()),
+        format!("{}", data[&uji_bashi].clone().unwrap().uji_bashi().unwrap()),
         "Jigoku no Orphee"
     );
 }
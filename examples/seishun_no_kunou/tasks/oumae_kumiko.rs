@@ -3,6 +3,7 @@ use std::fmt;
 use std::time::Duration;
 
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
 use futures_timer::Delay;
 
@@ -27,7 +28,11 @@ impl Task<String, Data> for OumaeKumiko {
         self.id.clone()
     }
 
-    async fn run(&self, _: HashMap<String, Input<'_, Data>>) -> Option<Data> {
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, Data>>,
+        _: Scope<String, Data>,
+    ) -> Option<Data> {
         Delay::new(Duration::from_secs(1)).await;
         Some(Data::OumaeKumiko(Run::new()))
     }
@@ -2,6 +2,7 @@ use std::fmt;
 use std::time::Duration;
 
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
 use futures_timer::Delay;
 use std::collections::HashMap;
@@ -36,7 +37,11 @@ impl Task<String, Data> for UjiBashi {
         self.dependencies.clone()
     }
 
-    async fn run(&self, inputs: HashMap<String, Input<'_, Data>>) -> Option<Data> {
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, Data>>,
+        _: Scope<String, Data>,
+    ) -> Option<Data> {
         Delay::new(Duration::from_secs(1)).await;
 
         let _run = inputs[&OumaeKumiko::id()]
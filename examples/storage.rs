@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use dag_flow::context::Context;
+use dag_flow::context::ShardedStorage;
+use dag_flow::context::Storage;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::executor;
+use futures::future;
+
+const TASKS: u64 = 100;
+
+fn main() {
+    let builder = Engine::builder();
+    builder.add_input("input".into());
+    for index in 0..TASKS {
+        builder.add_task(Add(index));
+    }
+
+    builder.add_task(Sum);
+
+    let engine = builder.build().unwrap();
+    let reads = Arc::new(AtomicUsize::new(0));
+    let context = Context::with_storage(Counting {
+        storage: ShardedStorage::new(8),
+        reads: reads.clone(),
+    });
+
+    context.set("input".into(), future::ready(Some(1)).boxed().shared());
+    executor::block_on(engine.run(context.clone()));
+
+    let sum = (0..TASKS).map(|index| index + 1).sum();
+    assert_eq!(context.try_get_ready(&"sum".into()), Some(Some(sum)));
+    assert!(reads.load(Ordering::Relaxed) as u64 > TASKS);
+    assert_eq!(context.keys().len() as u64, TASKS + 2);
+
+    let context = Context::new();
+    context.set("a", future::ready(1).boxed().shared());
+    context.set("b", future::ready(2).boxed().shared());
+    assert!(context.ready_values().is_empty());
+
+    let values = executor::block_on(context.snapshot());
+    assert_eq!(values, HashMap::from([("a", 1), ("b", 2)]));
+    assert_eq!(context.ready_values(), values);
+}
+
+struct Counting<K, V> {
+    storage: ShardedStorage<K, V>,
+    reads: Arc<AtomicUsize>,
+}
+
+impl<K, V> Storage<K, V> for Counting<K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.storage.get(key)
+    }
+
+    fn insert(&self, key: K, value: V) -> Option<V> {
+        self.storage.insert(key, value)
+    }
+
+    fn entries(&self) -> Vec<(K, V)> {
+        self.storage.entries()
+    }
+}
+
+struct Add(u64);
+
+impl Task<String, u64> for Add {
+    fn id(&self) -> String {
+        format!("add-{}", self.0)
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["input".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        Some(inputs["input"].clone().await? + self.0)
+    }
+}
+
+struct Sum;
+
+impl Task<String, u64> for Sum {
+    fn id(&self) -> String {
+        "sum".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        (0..TASKS).map(|index| format!("add-{index}")).collect()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        future::join_all(inputs.into_values())
+            .await
+            .into_iter()
+            .sum()
+    }
+}
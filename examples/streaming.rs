@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Blocking;
+use dag_flow::engine::Engine;
+use dag_flow::engine::LruCache;
+use dag_flow::engine::ThreadPool;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::StreamExt;
+use futures::executor;
+
+const ITEMS: u64 = 100;
+
+fn main() {
+    let builder = Engine::builder();
+    builder
+        .add_task(Numbers)
+        .add_task(Sum)
+        .add_task(Max)
+        .add_task(Ignore);
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+
+    executor::block_on(engine.run(context.clone()));
+
+    let outputs = executor::block_on(async {
+        (
+            context.get(&"numbers".into()).unwrap().await,
+            context.get(&"sum".into()).unwrap().await,
+            context.get(&"max".into()).unwrap().await,
+        )
+    });
+
+    assert_eq!(
+        outputs,
+        (Some(ITEMS), Some(ITEMS * (ITEMS + 1) / 2), Some(ITEMS))
+    );
+
+    // Dependents that only subscribe after awaiting every input, or never do,
+    // must not hold a small stream back.
+    let builder = Engine::builder();
+    builder
+        .add_task(Ticks { is_auto: true })
+        .add_task(Tally::new("later").manual())
+        .add_task(Tally::new("cached").cacheable())
+        .add_task(Blocking::new(Tally::new("blocking")))
+        .add_task(AwaitOnly)
+        .cache(LruCache::new(16))
+        .spawner(ThreadPool::new(2));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    executor::block_on(engine.run(context.clone()));
+
+    let sum = TICKS * (TICKS + 1) / 2;
+    let ready = |id: &str| context.try_get_ready(&id.into());
+    assert_eq!(ready("cached"), Some(Some(sum)));
+    assert_eq!(ready("blocking"), Some(Some(sum)));
+    assert_eq!(ready("await-only"), Some(Some(TICKS)));
+    assert_eq!(ready("later"), None);
+    assert!(!context.ready_values().contains_key("later"));
+
+    // Items are kept for a dependent that has not subscribed yet.
+    let later = executor::block_on(context.get(&"later".into()).unwrap());
+    assert_eq!(later, Some(sum));
+
+    // A streaming task runs even if it is not auto, since its subscribers
+    // never poll it.
+    let builder = Engine::builder();
+    builder
+        .add_task(Ticks { is_auto: false })
+        .add_task(Tally::new("sum"));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    executor::block_on(engine.run(context.clone()));
+    assert_eq!(context.try_get_ready(&"sum".into()), Some(Some(sum)));
+}
+
+const TICKS: u64 = 10;
+
+struct Numbers;
+
+impl Task<String, u64> for Numbers {
+    fn id(&self) -> String {
+        "numbers".into()
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_capacity(&self) -> usize {
+        4
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        for item in 1..=ITEMS {
+            assert!(scope.send(item).await);
+        }
+
+        Some(ITEMS)
+    }
+}
+
+struct Sum;
+
+impl Task<String, u64> for Sum {
+    fn id(&self) -> String {
+        "sum".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["numbers".into()]
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        let stream = scope.subscribe(&"numbers".into())?;
+        Some(stream.fold(0, |sum, item| async move { sum + item }).await)
+    }
+}
+
+struct Max;
+
+impl Task<String, u64> for Max {
+    fn id(&self) -> String {
+        "max".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["numbers".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        let stream = scope.subscribe(&"numbers".into())?;
+        let max = stream
+            .fold(0, |max, item| async move { max.max(item) })
+            .await;
+
+        // The final output is still available once the stream has ended.
+        assert_eq!(inputs["numbers"].clone().await, Some(max));
+        Some(max)
+    }
+}
+
+struct Ignore;
+
+impl Task<String, u64> for Ignore {
+    fn id(&self) -> String {
+        "ignore".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["numbers".into()]
+    }
+
+    async fn run(&self, _: HashMap<String, Input<'_, u64>>, _: Scope<String, u64>) -> Option<u64> {
+        None
+    }
+}
+
+struct Ticks {
+    is_auto: bool,
+}
+
+impl Task<String, u64> for Ticks {
+    fn id(&self) -> String {
+        "ticks".into()
+    }
+
+    fn is_auto(&self) -> bool {
+        self.is_auto
+    }
+
+    fn is_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_capacity(&self) -> usize {
+        2
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        for tick in 1..=TICKS {
+            assert!(scope.send(tick).await);
+        }
+
+        Some(TICKS)
+    }
+}
+
+struct Tally {
+    id: &'static str,
+    is_auto: bool,
+    is_cacheable: bool,
+}
+
+impl Tally {
+    fn new(id: &'static str) -> Self {
+        Self {
+            id,
+            is_auto: true,
+            is_cacheable: false,
+        }
+    }
+
+    fn manual(mut self) -> Self {
+        self.is_auto = false;
+        self
+    }
+
+    fn cacheable(mut self) -> Self {
+        self.is_cacheable = true;
+        self
+    }
+}
+
+impl Task<String, u64> for Tally {
+    fn id(&self) -> String {
+        self.id.into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["ticks".into()]
+    }
+
+    fn is_auto(&self) -> bool {
+        self.is_auto
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.is_cacheable
+    }
+
+    async fn run(
+        &self,
+        _: HashMap<String, Input<'_, u64>>,
+        scope: Scope<String, u64>,
+    ) -> Option<u64> {
+        let stream = scope.subscribe(&"ticks".into())?;
+        Some(stream.fold(0, |sum, item| async move { sum + item }).await)
+    }
+}
+
+struct AwaitOnly;
+
+impl Task<String, u64> for AwaitOnly {
+    fn id(&self) -> String {
+        "await-only".into()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec!["ticks".into()]
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        inputs["ticks"].clone().await
+    }
+}
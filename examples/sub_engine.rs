@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Engine;
+use dag_flow::engine::Observer;
+use dag_flow::engine::Progress;
+use dag_flow::engine::SubEngine;
+use dag_flow::engine::TaskProgress;
+use dag_flow::engine::TaskReport;
+use dag_flow::engine::TaskStatus;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::executor;
+
+fn main() {
+    let builder = Engine::builder();
+    builder
+        .add_input("input".into())
+        .add_task(Add::from("double", vec!["input", "input"]))
+        .add_task(Add::from("triple", vec!["double", "input"]));
+
+    let sub_engine = SubEngine::new("tripled".into(), builder.build().unwrap())
+        .input("one".into(), "input".into())
+        .output("triple".into())
+        .namespace(|id| format!("tripled/{id}"));
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let builder = Engine::builder();
+    builder
+        .add_task(Add::from("one", Vec::new()))
+        .add_task(sub_engine)
+        .add_task(Add::from("sum", vec!["one", "tripled"]))
+        .observer(Events(events.clone()));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    let progress = Progress::new();
+
+    let report = executor::block_on(engine.run_with_progress(context.clone(), &progress));
+
+    assert_eq!(
+        executor::block_on(context.get(&"sum".into()).unwrap()),
+        Some(4)
+    );
+
+    // The child tasks are reported under the sub-engine's namespace, and the
+    // child run counts towards the progress of the sub-engine.
+    let mut ids: Vec<_> = report.tasks.keys().map(String::as_str).collect();
+    ids.sort();
+    assert_eq!(
+        ids,
+        ["one", "sum", "tripled", "tripled/double", "tripled/triple"]
+    );
+
+    let events = events.lock().unwrap();
+    let position = |event| events.iter().position(|e| e == event).unwrap();
+    assert!(position("start tripled") < position("start tripled/double"));
+    assert!(position("finish tripled/double") < position("finish tripled/triple"));
+    assert!(position("progress tripled 2/2") < position("finish tripled"));
+    assert_eq!(progress.status(&"tripled".into()), Some(TaskStatus::Done));
+}
+
+struct Events(Arc<Mutex<Vec<String>>>);
+
+impl Observer<String> for Events {
+    fn on_start(&self, _: u64, id: &String) {
+        self.0.lock().unwrap().push(format!("start {id}"));
+    }
+
+    fn on_progress(&self, _: u64, id: &String, progress: &TaskProgress) {
+        self.0.lock().unwrap().push(format!(
+            "progress {id} {}/{}",
+            progress.done, progress.total
+        ));
+    }
+
+    fn on_finish(&self, _: u64, id: &String, _: &TaskReport) {
+        self.0.lock().unwrap().push(format!("finish {id}"));
+    }
+}
+
+struct Add {
+    id: String,
+    dependencies: Vec<String>,
+}
+
+impl Add {
+    fn from(id: &str, dependencies: Vec<&str>) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Task<String, u64> for Add {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        if self.dependencies.is_empty() {
+            return Some(1);
+        }
+
+        let mut sum = 0;
+        for dependency in &self.dependencies {
+            sum += inputs[dependency].clone().await?;
+        }
+
+        Some(sum)
+    }
+}
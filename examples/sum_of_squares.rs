@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::iter;
 use std::time::Duration;
 use std::time::Instant;
@@ -6,9 +7,12 @@ use std::time::Instant;
 use dag_flow::context::Context;
 use dag_flow::engine::Engine;
 use dag_flow::task::Input;
+use dag_flow::task::Scope;
 use dag_flow::task::Task;
+use futures::FutureExt;
 use futures::StreamExt;
 use futures::executor;
+use futures::future;
 use futures::stream::FuturesUnordered;
 use futures_timer::Delay;
 
@@ -38,19 +42,22 @@ fn main() {
         .chain(iter::once(Sum::id()))
         .collect();
 
-    let data: HashMap<_, _> = executor::block_on(
-        ids.iter()
-            .map(|id| {
-                let data = context.get(id).unwrap();
-                async move { (id, data.await.unwrap()) }
-            })
-            .collect::<FuturesUnordered<_>>()
-            .collect(),
-    );
+    let data = executor::block_on(context.snapshot());
 
     for (id, &number) in ids.iter().zip(NUMBERS) {
-        assert_eq!(data[id], number.pow(2));
+        assert_eq!(data[id], Some(number.pow(2)));
     }
+
+    let sum = NUMBERS.iter().map(|number| number.pow(2)).sum();
+    assert_eq!(data[&Sum::id()], Some(sum));
+    assert_eq!(context.try_get_ready(&Sum::id()), Some(Some(sum)));
+
+    let keys: HashSet<_> = context.keys().into_iter().collect();
+    assert_eq!(keys, ids.into_iter().collect());
+
+    let pending = "pending".to_string();
+    context.set(pending.clone(), future::pending().boxed().shared());
+    assert_eq!(context.try_get_ready(&pending), None);
 }
 
 struct Square {
@@ -76,7 +83,7 @@ impl Task<String, u64> for Square {
         self.id.clone()
     }
 
-    async fn run(&self, _: HashMap<String, Input<'_, u64>>) -> Option<u64> {
+    async fn run(&self, _: HashMap<String, Input<'_, u64>>, _: Scope<String, u64>) -> Option<u64> {
         Delay::new(Duration::from_secs(self.number)).await;
         Some(self.number.pow(2))
     }
@@ -111,7 +118,11 @@ impl Task<String, u64> for Sum {
         self.dependencies.clone()
     }
 
-    async fn run(&self, inputs: HashMap<String, Input<'_, u64>>) -> Option<u64> {
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
         self.numbers
             .iter()
             .enumerate()
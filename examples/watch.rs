@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dag_flow::context::Context;
+use dag_flow::context::ContextEvent;
+use dag_flow::engine::Engine;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::StreamExt;
+use futures::executor;
+use futures::future;
+use futures_timer::Delay;
+
+fn main() {
+    let builder = Engine::builder();
+    builder
+        .add_input("seed".into())
+        .add_task(Step::from("fetch", &["seed"]))
+        .add_task(Step::from("parse", &["fetch"]))
+        .add_task(Step::from("render", &["parse"]));
+
+    let engine = builder.build().unwrap();
+    let context = Context::new();
+    let mut watcher = context.subscribe();
+
+    context.set("seed".into(), future::ready(Some(1)).boxed().shared());
+
+    let (_, events) = executor::block_on(future::join(engine.run(context.clone()), async {
+        let mut events = Vec::new();
+        while let Some(event) = watcher.next().await {
+            let is_done = event == ContextEvent::Resolved("render".into());
+            events.push(event);
+
+            if is_done {
+                break;
+            }
+        }
+
+        events
+    }));
+
+    let position = |event| events.iter().position(|e| *e == event).unwrap();
+    for id in ["seed", "fetch", "parse", "render"] {
+        assert!(
+            position(ContextEvent::Inserted(id.into()))
+                < position(ContextEvent::Resolved(id.into()))
+        );
+    }
+
+    assert!(
+        position(ContextEvent::Resolved("fetch".into()))
+            < position(ContextEvent::Resolved("parse".into()))
+    );
+    assert_eq!(context.try_get_ready(&"render".into()), Some(Some(4)));
+
+    let mut watcher = context.subscribe();
+    context.set("seed".into(), future::ready(Some(2)).boxed().shared());
+    assert_eq!(
+        executor::block_on(watcher.next()),
+        Some(ContextEvent::Replaced("seed".into()))
+    );
+}
+
+struct Step {
+    id: String,
+    dependencies: Vec<String>,
+}
+
+impl Step {
+    fn from(id: &str, dependencies: &[&str]) -> Self {
+        Self {
+            id: id.into(),
+            dependencies: dependencies.iter().map(|&id| id.into()).collect(),
+        }
+    }
+}
+
+impl Task<String, u64> for Step {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let inputs = future::join_all(inputs.into_values()).await;
+        Delay::new(Duration::from_millis(10)).await;
+
+        Some(inputs.into_iter().sum::<Option<u64>>()? + 1)
+    }
+}
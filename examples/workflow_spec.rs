@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use dag_flow::context::Context;
+use dag_flow::engine::Registry;
+use dag_flow::engine::WorkflowSpec;
+use dag_flow::task::Input;
+use dag_flow::task::Scope;
+use dag_flow::task::Task;
+use futures::FutureExt;
+use futures::executor;
+use serde_json::Value;
+
+const SPEC: &str = r#"{
+    "inputs": ["x"],
+    "tasks": [
+        { "id": "double", "kind": "scale", "dependencies": ["x"], "params": { "factor": 2 } },
+        { "id": "triple", "kind": "scale", "dependencies": ["x"], "params": { "factor": 3 } },
+        { "id": "sum", "kind": "sum", "dependencies": ["double", "triple"] }
+    ]
+}"#;
+
+fn main() {
+    let mut registry: Registry<String, u64, Value> = Registry::new();
+    registry
+        .register("scale", |spec| Scale {
+            id: spec.id.clone(),
+            dependency: spec.dependencies[0].clone(),
+            factor: spec.params.as_ref().unwrap()["factor"].as_u64().unwrap(),
+        })
+        .register("sum", |spec| Sum {
+            id: spec.id.clone(),
+        });
+
+    let spec: WorkflowSpec<String, Value> = serde_json::from_str(SPEC).unwrap();
+    let engine = spec.into_builder(&registry).unwrap().build().unwrap();
+
+    let context = Context::new();
+    context.set("x".into(), async { Some(7) }.boxed().shared());
+    executor::block_on(engine.run(context.clone()));
+
+    assert_eq!(
+        executor::block_on(context.get(&"sum".into()).unwrap()),
+        Some(35)
+    );
+
+    let spec: WorkflowSpec<String, Value> = serde_json::from_str(
+        r#"{ "tasks": [{ "id": "a", "kind": "unknown" }, { "id": "b", "kind": "sum", "dependencies": ["c"] }] }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        format!("{}", spec.into_builder(&registry).err().unwrap()),
+        r#"unknown task kinds: ["unknown"]"#
+    );
+
+    let spec: WorkflowSpec<String, Value> = serde_json::from_str(
+        r#"{ "tasks": [{ "id": "b", "kind": "sum", "dependencies": ["c"] }] }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        format!(
+            "{}",
+            spec.into_builder(&registry).unwrap().build().err().unwrap()
+        ),
+        r#"unknown dependencies: ["c"]"#
+    );
+}
+
+struct Scale {
+    id: String,
+    dependency: String,
+    factor: u64,
+}
+
+impl Task<String, u64> for Scale {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        Some(inputs[&self.dependency].clone().await? * self.factor)
+    }
+}
+
+struct Sum {
+    id: String,
+}
+
+impl Task<String, u64> for Sum {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    async fn run(
+        &self,
+        inputs: HashMap<String, Input<'_, u64>>,
+        _: Scope<String, u64>,
+    ) -> Option<u64> {
+        let mut sum = 0;
+        for input in inputs.values() {
+            sum += input.clone().await?;
+        }
+
+        Some(sum)
+    }
+}
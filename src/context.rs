@@ -1,41 +1,222 @@
+use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::Hash;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::Mutex;
+use std::task;
+use std::task::Poll;
 
+use futures::FutureExt;
+use futures::Stream;
+use futures::channel::mpsc;
+use futures::future;
 use futures::future::BoxFuture;
 use futures::future::Shared;
 
+mod storage;
+pub use storage::ShardedStorage;
+pub use storage::Storage;
+
 pub type Value<'a, T> = Shared<BoxFuture<'a, T>>;
 
-#[derive(Clone, Debug)]
+pub(crate) type Params = Arc<dyn Any + Send + Sync>;
+
+#[derive(Clone)]
 pub struct Context<'a, K, V> {
-    context: Arc<RwLock<HashMap<K, Value<'a, V>>>>,
+    storage: Arc<dyn Storage<K, Value<'a, V>> + 'a>,
+    params: Option<Params>,
+    watchers: Arc<Mutex<Vec<mpsc::UnboundedSender<ContextEvent<K>>>>>,
 }
 
-impl<K, V> Context<'_, K, V> {
+impl<'a, K, V> Context<'a, K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'a,
+    V: Send + Sync + 'a,
+{
     pub fn new() -> Self {
+        Self::with_storage(ShardedStorage::default())
+    }
+}
+
+impl<'a, K, V> Context<'a, K, V> {
+    pub fn with_storage<S>(storage: S) -> Self
+    where
+        S: Storage<K, Value<'a, V>> + 'a,
+    {
         Self {
-            context: Arc::new(RwLock::new(HashMap::new())),
+            storage: Arc::new(storage),
+            params: None,
+            watchers: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// Events for every change from now on, shared by all clones of the
+    /// context. Only values set after subscribing report `Resolved`, when
+    /// whoever awaits them first sees them complete.
+    pub fn subscribe(&self) -> Watcher<K> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.watchers.lock().unwrap().push(sender);
+        Watcher(receiver)
+    }
+
+    /// Attaches parameters for the run, which every task can read with
+    /// `Scope::params`.
+    pub fn with_params<P>(self, params: P) -> Self
+    where
+        P: Any + Send + Sync,
+    {
+        self.with_shared_params(Some(Arc::new(params)))
+    }
+
+    pub fn params<P>(&self) -> Option<Arc<P>>
+    where
+        P: Any + Send + Sync,
+    {
+        self.params.clone()?.downcast().ok()
+    }
+
+    pub(crate) fn with_shared_params(mut self, params: Option<Params>) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub(crate) fn shared_params(&self) -> Option<Params> {
+        self.params.clone()
+    }
 }
 
-impl<K, V> Default for Context<'_, K, V> {
+impl<'a, K, V> Default for Context<'a, K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync + 'a,
+    V: Send + Sync + 'a,
+{
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<K, V> fmt::Debug for Context<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<'a, K, V> Context<'a, K, V>
 where
     K: Eq + Hash,
 {
     pub fn get(&self, key: &K) -> Option<Value<'a, V>> {
-        self.context.read().unwrap().get(key).cloned()
+        self.storage.get(key)
     }
+}
 
+impl<'a, K, V> Context<'a, K, V>
+where
+    K: Clone + Eq + Hash + Send + 'a,
+    V: Clone + Send + Sync + 'a,
+{
     pub fn set(&self, key: K, value: Value<'a, V>) -> Option<Value<'a, V>> {
-        self.context.write().unwrap().insert(key, value)
+        if self.watchers.lock().unwrap().is_empty() {
+            return self.storage.insert(key, value);
+        }
+
+        let watchers = self.watchers.clone();
+        let resolved = key.clone();
+        let value = async move {
+            let value = value.await;
+            notify(&watchers, ContextEvent::Resolved(resolved));
+            value
+        }
+        .boxed()
+        .shared();
+
+        let previous = self.storage.insert(key.clone(), value);
+        let event = match previous {
+            Some(_) => ContextEvent::Replaced(key),
+            None => ContextEvent::Inserted(key),
+        };
+
+        notify(&self.watchers, event);
+        previous
+    }
+}
+
+impl<K, V> Context<'_, K, V>
+where
+    K: Clone + Eq + Hash,
+{
+    pub fn keys(&self) -> Vec<K> {
+        self.storage.keys()
+    }
+}
+
+impl<K, V> Context<'_, K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    /// The value for `key` if it has already resolved, without polling it.
+    pub fn try_get_ready(&self, key: &K) -> Option<V> {
+        self.storage.get(key)?.peek().cloned()
     }
 }
+
+impl<K, V> Context<'_, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// The values that have already resolved, without polling the rest. Unlike
+    /// `snapshot`, this leaves tasks that are not auto untouched.
+    pub fn ready_values(&self) -> HashMap<K, V> {
+        self.storage
+            .entries()
+            .into_iter()
+            .flat_map(|(key, value)| Some((key, value.peek()?.clone())))
+            .collect()
+    }
+
+    /// Awaits every value, which also drives those that nothing has polled
+    /// yet, such as tasks that are not auto.
+    pub async fn snapshot(&self) -> HashMap<K, V> {
+        let (keys, values): (Vec<_>, Vec<_>) = self.storage.entries().into_iter().unzip();
+
+        let values = future::join_all(values).await;
+        keys.into_iter().zip(values).collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ContextEvent<K> {
+    Inserted(K),
+    Replaced(K),
+    Resolved(K),
+}
+
+/// A stream of `ContextEvent`s from `Context::subscribe`, which never ends
+/// while any clone of the context is alive.
+#[derive(Debug)]
+pub struct Watcher<K>(mpsc::UnboundedReceiver<ContextEvent<K>>);
+
+impl<K> Stream for Watcher<K> {
+    type Item = ContextEvent<K>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx)
+    }
+}
+
+// Drops the senders of watchers that have been dropped.
+fn notify<K>(watchers: &Mutex<Vec<mpsc::UnboundedSender<ContextEvent<K>>>>, event: ContextEvent<K>)
+where
+    K: Clone,
+{
+    watchers
+        .lock()
+        .unwrap()
+        .retain(|watcher| watcher.unbounded_send(event.clone()).is_ok());
+}
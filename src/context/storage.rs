@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::hash::RandomState;
+use std::num::NonZeroUsize;
+use std::sync::RwLock;
+use std::thread;
+
+/// Where a `Context` keeps its values, shared by every clone of the context
+/// and read concurrently by all running tasks.
+pub trait Storage<K, V>: Send + Sync {
+    fn get(&self, key: &K) -> Option<V>;
+
+    /// Returns the value that was replaced, if any.
+    fn insert(&self, key: K, value: V) -> Option<V>;
+
+    fn entries(&self) -> Vec<(K, V)>;
+
+    fn keys(&self) -> Vec<K> {
+        self.entries().into_iter().map(|(key, _)| key).collect()
+    }
+}
+
+/// The default storage, which spreads keys over independently locked shards so
+/// that readers of different keys rarely wait on the same lock.
+#[derive(Debug)]
+pub struct ShardedStorage<K, V> {
+    shards: Box<[RwLock<HashMap<K, V>>]>,
+    hasher: RandomState,
+}
+
+impl<K, V> ShardedStorage<K, V> {
+    pub fn new(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+}
+
+impl<K, V> Default for ShardedStorage<K, V> {
+    fn default() -> Self {
+        // Four shards per available thread.
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get) * 4)
+    }
+}
+
+impl<K, V> ShardedStorage<K, V>
+where
+    K: Hash,
+{
+    fn shard(&self, key: &K) -> &RwLock<HashMap<K, V>> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+impl<K, V> Storage<K, V> for ShardedStorage<K, V>
+where
+    K: Clone + Eq + Hash + Send + Sync,
+    V: Clone + Send + Sync,
+{
+    fn get(&self, key: &K) -> Option<V> {
+        self.shard(key).read().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shard(&key).write().unwrap().insert(key, value)
+    }
+
+    fn entries(&self) -> Vec<(K, V)> {
+        self.shards
+            .iter()
+            .flat_map(|shard| {
+                let shard = shard.read().unwrap();
+                shard
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn keys(&self) -> Vec<K> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.read().unwrap().keys().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+}
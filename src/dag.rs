@@ -0,0 +1,508 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::sync::Arc;
+
+mod diff;
+pub use diff::DagDiff;
+
+mod metrics;
+pub use metrics::LongestPath;
+
+mod order;
+use order::Order;
+
+mod reduction;
+
+/// A directed acyclic graph whose nodes carry a `V` and edges an `E`. `new`
+/// and `builder` are for graphs without values; otherwise start from
+/// `DagBuilder::default()`.
+#[derive(Debug)]
+pub struct Dag<N, V = (), E = ()> {
+    graph: Arc<HashMap<N, NodeData<N, V, E>>>,
+    order: Arc<Vec<N>>,
+}
+
+impl<N> Dag<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> DagBuilder<N> {
+        DagBuilder::new()
+    }
+}
+
+impl<N, V, E> Clone for Dag<N, V, E> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph.clone(),
+            order: self.order.clone(),
+        }
+    }
+}
+
+impl<N, V, E> Default for Dag<N, V, E> {
+    fn default() -> Self {
+        Self {
+            graph: Arc::new(HashMap::new()),
+            order: Arc::new(Vec::new()),
+        }
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn graph(&self) -> Arc<HashMap<N, NodeData<N, V, E>>> {
+        self.graph.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.graph.is_empty()
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.graph.contains_key(node)
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        contains_edge(&self.graph, from, to)
+    }
+
+    pub fn node_value(&self, node: &N) -> Option<&V> {
+        self.graph.get(node).map(|data| &data.value)
+    }
+
+    pub fn edge_value(&self, from: &N, to: &N) -> Option<&E> {
+        self.graph.get(from)?.edge_value(to)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.order.iter()
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Edge<&N>> {
+        edges(&self.graph)
+    }
+
+    /// Every node comes after all of its in-neighbors. The order is fixed when
+    /// the DAG is built.
+    pub fn topological_order(&self) -> &[N] {
+        &self.order
+    }
+
+    // Maps each node to every node it reaches.
+    fn descendants(&self) -> HashMap<&N, HashSet<&N>> {
+        let mut descendants: HashMap<_, HashSet<_>> = HashMap::with_capacity(self.len());
+        for node in self.order.iter().rev() {
+            let out_neighbors = &self.graph[node].out_neighbors;
+            let mut reachable: HashSet<_> = out_neighbors.iter().collect();
+
+            for out_neighbor in out_neighbors {
+                reachable.extend(&descendants[out_neighbor]);
+            }
+
+            descendants.insert(node, reachable);
+        }
+
+        descendants
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+    V: Clone,
+    E: Clone,
+{
+    pub fn into_builder(self) -> DagBuilder<N, V, E> {
+        DagBuilder {
+            order: Some(Order::from_nodes(self.order.iter())),
+            graph: Arc::unwrap_or_clone(self.graph),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NodeData<N, V = (), E = ()> {
+    pub in_neighbors: Vec<N>,
+    pub out_neighbors: Vec<N>,
+    pub value: V,
+    // Keyed by out-neighbor, and only set for edges added with a value.
+    edge_values: HashMap<N, E>,
+}
+
+impl<N, V, E> NodeData<N, V, E>
+where
+    V: Default,
+{
+    pub fn new() -> Self {
+        Self::from(Vec::new(), Vec::new())
+    }
+
+    pub fn from(in_neighbors: Vec<N>, out_neighbors: Vec<N>) -> Self {
+        Self {
+            in_neighbors,
+            out_neighbors,
+            value: V::default(),
+            edge_values: HashMap::new(),
+        }
+    }
+}
+
+impl<N, V, E> NodeData<N, V, E> {
+    fn with_value(value: V) -> Self {
+        Self {
+            in_neighbors: Vec::new(),
+            out_neighbors: Vec::new(),
+            value,
+            edge_values: HashMap::new(),
+        }
+    }
+}
+
+impl<N, V, E> NodeData<N, V, E>
+where
+    N: Eq + Hash,
+{
+    pub fn edge_value(&self, out_neighbor: &N) -> Option<&E> {
+        self.edge_values.get(out_neighbor)
+    }
+}
+
+impl<N, V, E> Default for NodeData<N, V, E>
+where
+    V: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DagBuilder<N, V = (), E = ()> {
+    graph: HashMap<N, NodeData<N, V, E>>,
+    // `None` once `add_edge` has broken the order, until `try_add_edge`
+    // rebuilds it.
+    order: Option<Order<N>>,
+}
+
+impl<N> DagBuilder<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<N, V, E> Default for DagBuilder<N, V, E> {
+    fn default() -> Self {
+        Self {
+            graph: HashMap::new(),
+            order: Some(Order::new()),
+        }
+    }
+}
+
+impl<N, V, E> DagBuilder<N, V, E>
+where
+    N: Eq + Hash,
+    V: Default,
+{
+    pub fn add_node(&mut self, node: N) -> &mut Self {
+        self.graph.entry(node).or_default();
+        self
+    }
+}
+
+impl<N, V, E> DagBuilder<N, V, E>
+where
+    N: Eq + Hash,
+{
+    /// Adds `node` or replaces its value.
+    pub fn add_node_with(&mut self, node: N, value: V) -> &mut Self {
+        match self.graph.entry(node) {
+            Entry::Occupied(mut entry) => entry.get_mut().value = value,
+            Entry::Vacant(entry) => {
+                entry.insert(NodeData::with_value(value));
+            }
+        }
+
+        self
+    }
+
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.graph.contains_key(node)
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        contains_edge(&self.graph, from, to)
+    }
+
+    pub fn edge_value(&self, from: &N, to: &N) -> Option<&E> {
+        self.graph.get(from)?.edge_value(to)
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Edge<&N>> {
+        edges(&self.graph)
+    }
+
+    /// Also removes every edge from or to `node`.
+    pub fn remove_node(&mut self, node: &N) -> &mut Self {
+        let Some(NodeData {
+            in_neighbors,
+            out_neighbors,
+            ..
+        }) = self.graph.remove(node)
+        else {
+            return self;
+        };
+
+        for in_neighbor in &in_neighbors {
+            if let Some(data) = self.graph.get_mut(in_neighbor) {
+                data.out_neighbors
+                    .retain(|out_neighbor| out_neighbor != node);
+                data.edge_values.remove(node);
+            }
+        }
+
+        for out_neighbor in &out_neighbors {
+            if let Some(data) = self.graph.get_mut(out_neighbor) {
+                data.in_neighbors.retain(|in_neighbor| in_neighbor != node);
+            }
+        }
+
+        if let Some(order) = &mut self.order {
+            order.remove(node);
+        }
+
+        self
+    }
+
+    /// Keeps both nodes.
+    pub fn remove_edge(&mut self, Edge { from, to }: &Edge<N>) -> &mut Self {
+        if let Some(data) = self.graph.get_mut(from) {
+            data.out_neighbors.retain(|out_neighbor| out_neighbor != to);
+            data.edge_values.remove(to);
+        }
+
+        if let Some(data) = self.graph.get_mut(to) {
+            data.in_neighbors.retain(|in_neighbor| in_neighbor != from);
+        }
+
+        self
+    }
+}
+
+impl<N, V, E> DagBuilder<N, V, E>
+where
+    N: Clone + Eq + Hash,
+    V: Default,
+{
+    pub fn add_edge(&mut self, edge: Edge<N>) -> &mut Self {
+        self.insert_edge(edge, None)
+    }
+
+    /// Like `add_edge`, but also sets the value of the edge, replacing it if
+    /// the edge already exists.
+    pub fn add_edge_with(&mut self, edge: Edge<N>, value: E) -> &mut Self {
+        self.insert_edge(edge, Some(value))
+    }
+
+    fn insert_edge(&mut self, Edge { from, to }: Edge<N>, value: Option<E>) -> &mut Self {
+        if from == to {
+            return self;
+        }
+
+        let graph = &mut self.graph;
+        if contains_edge(graph, &from, &to) {
+            if let Some(value) = value {
+                graph.get_mut(&from).unwrap().edge_values.insert(to, value);
+            }
+
+            return self;
+        }
+
+        if let Some(order) = &mut self.order {
+            order.insert_edge(&from, &to);
+
+            if !order.is_ordered(&from, &to) {
+                self.order = None;
+            }
+        }
+
+        if !graph.contains_key(&from) {
+            graph.insert(from.clone(), NodeData::new());
+        }
+
+        let data = graph.get_mut(&from).unwrap();
+        data.out_neighbors.push(to.clone());
+        if let Some(value) = value {
+            data.edge_values.insert(to.clone(), value);
+        }
+
+        graph.entry(to).or_default().in_neighbors.push(from);
+        self
+    }
+
+    /// Like `add_edge`, but rejects an edge that would close a cycle, including
+    /// a self-loop. Keeps a topological order up to date so that each check
+    /// only visits the nodes ordered between `from` and `to`.
+    pub fn try_add_edge(&mut self, edge: Edge<N>) -> Result<&mut Self, AddEdgeError<N>> {
+        self.try_insert_edge(edge, None)
+    }
+
+    /// Like `try_add_edge`, but also sets the value of the edge as
+    /// `add_edge_with` does.
+    pub fn try_add_edge_with(
+        &mut self,
+        edge: Edge<N>,
+        value: E,
+    ) -> Result<&mut Self, AddEdgeError<N>> {
+        self.try_insert_edge(edge, Some(value))
+    }
+
+    fn try_insert_edge(
+        &mut self,
+        edge: Edge<N>,
+        value: Option<E>,
+    ) -> Result<&mut Self, AddEdgeError<N>> {
+        let Edge { from, to } = &edge;
+        if from == to {
+            return Err(AddEdgeError {
+                cycle: vec![from.clone()],
+            });
+        }
+
+        if contains_edge(&self.graph, from, to) {
+            return Ok(self.insert_edge(edge, value));
+        }
+
+        if self.order.is_none() {
+            self.order = Order::from_graph(&self.graph);
+        }
+
+        let result = match &mut self.order {
+            Some(order) => order.add_edge(&self.graph, from, to),
+            None => order::find_path(&self.graph, from, to).map_or(Ok(()), Err),
+        };
+
+        if let Err(mut cycle) = result {
+            cycle.rotate_right(1);
+            return Err(AddEdgeError { cycle });
+        }
+
+        let order = self.order.take();
+        self.insert_edge(edge, value);
+        self.order = order;
+
+        Ok(self)
+    }
+}
+
+impl<N, V, E> DagBuilder<N, V, E>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn build(self) -> Result<Dag<N, V, E>, BuildDagError> {
+        let graph = self.graph;
+        let Some(order) = topological_sort(&graph) else {
+            Err(DagErrorKind::Cycle)?
+        };
+
+        let order = order.into_iter().cloned().collect();
+        Ok(Dag {
+            graph: Arc::new(graph),
+            order: Arc::new(order),
+        })
+    }
+}
+
+// Kahn's algorithm, which fails if the graph has a cycle.
+fn topological_sort<N, V, E>(graph: &HashMap<N, NodeData<N, V, E>>) -> Option<Vec<&N>>
+where
+    N: Eq + Hash,
+{
+    let mut order = Vec::with_capacity(graph.len());
+    let mut in_degrees: HashMap<_, _> = graph
+        .iter()
+        .map(|(node, NodeData { in_neighbors, .. })| (node, in_neighbors.len()))
+        .collect();
+
+    let mut queue: VecDeque<_> = in_degrees
+        .iter()
+        .flat_map(|(&node, &in_degree)| if in_degree > 0 { None } else { Some(node) })
+        .collect();
+
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for out_neighbor in &graph[node].out_neighbors {
+            let in_degree = in_degrees.get_mut(out_neighbor).unwrap();
+            *in_degree -= 1;
+
+            if *in_degree == 0 {
+                queue.push_back(out_neighbor);
+            }
+        }
+    }
+
+    (order.len() == graph.len()).then_some(order)
+}
+
+fn contains_edge<N, V, E>(graph: &HashMap<N, NodeData<N, V, E>>, from: &N, to: &N) -> bool
+where
+    N: Eq + Hash,
+{
+    graph
+        .get(from)
+        .is_some_and(|NodeData { out_neighbors, .. }| out_neighbors.contains(to))
+}
+
+fn edges<N, V, E>(graph: &HashMap<N, NodeData<N, V, E>>) -> impl Iterator<Item = Edge<&N>> {
+    graph
+        .iter()
+        .flat_map(|(from, NodeData { out_neighbors, .. })| {
+            out_neighbors.iter().map(move |to| Edge::new(from, to))
+        })
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Edge<N> {
+    pub from: N,
+    pub to: N,
+}
+
+impl<N> Edge<N> {
+    pub fn new(from: N, to: N) -> Self {
+        Self { from, to }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+#[error("edge would close a cycle: {cycle:?}")]
+pub struct AddEdgeError<N> {
+    cycle: Vec<N>,
+}
+
+impl<N> AddEdgeError<N> {
+    /// The nodes of the cycle, starting with both ends of the rejected edge.
+    pub fn cycle(&self) -> &[N] {
+        &self.cycle
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+#[error(transparent)]
+pub struct BuildDagError(#[from] DagErrorKind);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+enum DagErrorKind {
+    #[error("cycle detected in directed graph")]
+    Cycle,
+}
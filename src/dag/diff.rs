@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Write;
+use std::hash::Hash;
+
+use super::Dag;
+use super::Edge;
+
+/// The structural changes from one DAG to another, in topological order of the
+/// DAG each change belongs to.
+#[derive(Clone, Debug)]
+pub struct DagDiff<N> {
+    added_nodes: Vec<N>,
+    removed_nodes: Vec<N>,
+    added_edges: Vec<Edge<N>>,
+    removed_edges: Vec<Edge<N>>,
+    changed_edges: Vec<Edge<N>>,
+    changed_ancestors: Vec<N>,
+    // The nodes and edges of the new DAG, kept to render the unchanged part.
+    nodes: Vec<N>,
+    edges: Vec<Edge<N>>,
+}
+
+impl<N> DagDiff<N> {
+    pub fn added_nodes(&self) -> &[N] {
+        &self.added_nodes
+    }
+
+    pub fn removed_nodes(&self) -> &[N] {
+        &self.removed_nodes
+    }
+
+    pub fn added_edges(&self) -> &[Edge<N>] {
+        &self.added_edges
+    }
+
+    pub fn removed_edges(&self) -> &[Edge<N>] {
+        &self.removed_edges
+    }
+
+    /// Edges in both DAGs whose values differ.
+    pub fn changed_edges(&self) -> &[Edge<N>] {
+        &self.changed_edges
+    }
+
+    /// Nodes in both DAGs whose transitive ancestors differ.
+    pub fn changed_ancestors(&self) -> &[N] {
+        &self.changed_ancestors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+    }
+}
+
+impl<N> DagDiff<N>
+where
+    N: Clone + Display + Eq + Hash,
+{
+    /// The union of both DAGs in Graphviz DOT format, with added nodes and
+    /// edges in green, removed ones dashed in red, and changed edges and nodes
+    /// whose ancestors changed in orange.
+    pub fn to_dot(&self) -> String {
+        let added_nodes: HashSet<_> = self.added_nodes.iter().collect();
+        let changed_ancestors: HashSet<_> = self.changed_ancestors.iter().collect();
+        let added_edges: HashSet<_> = self.added_edges.iter().collect();
+        let changed_edges: HashSet<_> = self.changed_edges.iter().collect();
+
+        let mut dot = String::from("digraph {\n");
+        for node in &self.nodes {
+            let attributes = if added_nodes.contains(node) {
+                " [color=green]"
+            } else if changed_ancestors.contains(node) {
+                " [color=orange]"
+            } else {
+                ""
+            };
+
+            writeln!(dot, "    {}{attributes};", quote(node)).unwrap();
+        }
+
+        for node in &self.removed_nodes {
+            writeln!(dot, "    {} [color=red, style=dashed];", quote(node)).unwrap();
+        }
+
+        for edge @ Edge { from, to } in &self.edges {
+            let attributes = if added_edges.contains(edge) {
+                " [color=green]"
+            } else if changed_edges.contains(edge) {
+                " [color=orange]"
+            } else {
+                ""
+            };
+
+            writeln!(dot, "    {} -> {}{attributes};", quote(from), quote(to)).unwrap();
+        }
+
+        for Edge { from, to } in &self.removed_edges {
+            writeln!(
+                dot,
+                "    {} -> {} [color=red, style=dashed];",
+                quote(from),
+                quote(to)
+            )
+            .unwrap();
+        }
+
+        dot.push('}');
+        dot
+    }
+}
+
+impl<N> Display for DagDiff<N>
+where
+    N: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.added_nodes {
+            writeln!(f, "+ {node}")?;
+        }
+
+        for node in &self.removed_nodes {
+            writeln!(f, "- {node}")?;
+        }
+
+        for Edge { from, to } in &self.added_edges {
+            writeln!(f, "+ {from} -> {to}")?;
+        }
+
+        for Edge { from, to } in &self.removed_edges {
+            writeln!(f, "- {from} -> {to}")?;
+        }
+
+        for Edge { from, to } in &self.changed_edges {
+            writeln!(f, "~ {from} -> {to}")?;
+        }
+
+        for node in &self.changed_ancestors {
+            writeln!(f, "~ {node}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+{
+    /// The changes to the structure that turn `self` into `other`, ignoring
+    /// node values. Edges in both whose values differ count as changed.
+    pub fn diff(&self, other: &Dag<N, V, E>) -> DagDiff<N>
+    where
+        E: PartialEq,
+    {
+        let outside = |dag: &Self, other: &Self| -> Vec<N> {
+            dag.nodes()
+                .filter(|node| !other.contains_node(node))
+                .cloned()
+                .collect()
+        };
+
+        let edges_outside = |dag: &Self, other: &Self| -> Vec<Edge<N>> {
+            dag.ordered_edges()
+                .filter(|Edge { from, to }| !other.contains_edge(from, to))
+                .collect()
+        };
+
+        let changed_edges = other
+            .ordered_edges()
+            .filter(|Edge { from, to }| {
+                self.contains_edge(from, to)
+                    && self.edge_value(from, to) != other.edge_value(from, to)
+            })
+            .collect();
+
+        let ancestors = self.ancestors();
+        let other_ancestors = other.ancestors();
+        let changed_ancestors = other
+            .nodes()
+            .filter(|node| {
+                ancestors
+                    .get(node)
+                    .is_some_and(|ancestors| *ancestors != other_ancestors[node])
+            })
+            .cloned()
+            .collect();
+
+        DagDiff {
+            added_nodes: outside(other, self),
+            removed_nodes: outside(self, other),
+            added_edges: edges_outside(other, self),
+            removed_edges: edges_outside(self, other),
+            changed_edges,
+            changed_ancestors,
+            nodes: other.nodes().cloned().collect(),
+            edges: other.ordered_edges().collect(),
+        }
+    }
+
+    // Edges in topological order of their source.
+    fn ordered_edges(&self) -> impl Iterator<Item = Edge<N>> {
+        self.order.iter().flat_map(|from| {
+            self.graph[from]
+                .out_neighbors
+                .iter()
+                .map(move |to| Edge::new(from.clone(), to.clone()))
+        })
+    }
+
+    // Maps each node to every node that reaches it.
+    fn ancestors(&self) -> HashMap<&N, HashSet<&N>> {
+        let mut ancestors: HashMap<_, HashSet<_>> = HashMap::with_capacity(self.len());
+        for node in self.order.iter() {
+            let in_neighbors = &self.graph[node].in_neighbors;
+            let mut reaching: HashSet<_> = in_neighbors.iter().collect();
+
+            for in_neighbor in in_neighbors {
+                reaching.extend(&ancestors[in_neighbor]);
+            }
+
+            ancestors.insert(node, reaching);
+        }
+
+        ancestors
+    }
+}
+
+fn quote<N>(node: &N) -> String
+where
+    N: Display,
+{
+    format!("{:?}", node.to_string())
+}
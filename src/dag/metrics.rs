@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::Dag;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LongestPath<N> {
+    pub cost: u64,
+    pub nodes: Vec<N>,
+}
+
+impl<N> LongestPath<N> {
+    pub fn new() -> Self {
+        Self {
+            cost: 0,
+            nodes: Vec::new(),
+        }
+    }
+}
+
+impl<N> Default for LongestPath<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+{
+    /// The number of nodes on the longest chain.
+    pub fn depth(&self) -> usize {
+        self.layers().values().max().map_or(0, |layer| layer + 1)
+    }
+
+    /// The size of the largest set of nodes where none reaches another, i.e.
+    /// the most nodes that can run at the same time. By Dilworth's theorem it
+    /// is the number of nodes minus a maximum matching between each node and
+    /// its descendants, found with Kuhn's augmenting paths. Descendants are
+    /// explored through the edges rather than a precomputed closure, so each
+    /// search is linear in the size of the DAG.
+    pub fn width(&self) -> usize {
+        let mut matches = Matches::default();
+
+        // Matching every node with an out-neighbor first leaves few searches,
+        // e.g. none for a chain.
+        for node in self.order.iter() {
+            let out_neighbors = &self.graph[node].out_neighbors;
+            if let Some(out_neighbor) = out_neighbors
+                .iter()
+                .find(|out_neighbor| !matches.right.contains_key(out_neighbor))
+            {
+                matches.insert(node, out_neighbor);
+            }
+        }
+
+        for node in self.order.iter() {
+            if !matches.left.contains_key(node) {
+                self.augment(node, &mut matches);
+            }
+        }
+
+        self.len() - matches.left.len()
+    }
+
+    /// The length of the longest path from a source to each node, so every
+    /// node is in a later layer than its in-neighbors.
+    pub fn layers(&self) -> HashMap<N, usize> {
+        let mut layers: HashMap<_, _> = HashMap::with_capacity(self.len());
+        for node in self.order.iter() {
+            let layer = self.graph[node]
+                .in_neighbors
+                .iter()
+                .map(|in_neighbor| layers[in_neighbor] + 1)
+                .max()
+                .unwrap_or(0);
+
+            layers.insert(node.clone(), layer);
+        }
+
+        layers
+    }
+
+    /// The path with the highest total node cost.
+    pub fn longest_path<F>(&self, cost: F) -> LongestPath<N>
+    where
+        F: Fn(&N) -> u64,
+    {
+        let mut paths: HashMap<&N, (u64, Option<&N>)> = HashMap::with_capacity(self.len());
+        for node in self.order.iter() {
+            let (total, previous) = self.graph[node]
+                .in_neighbors
+                .iter()
+                .map(|in_neighbor| (paths[in_neighbor].0, Some(in_neighbor)))
+                .max_by_key(|(total, _)| *total)
+                .unwrap_or((0, None));
+
+            paths.insert(node, (total + cost(node), previous));
+        }
+
+        let Some((&end, &(cost, _))) = paths.iter().max_by_key(|(_, (total, _))| *total) else {
+            return LongestPath::new();
+        };
+
+        let mut node = end;
+        let mut nodes = vec![node.clone()];
+        while let (_, Some(previous)) = paths[node] {
+            node = previous;
+            nodes.push(node.clone());
+        }
+
+        nodes.reverse();
+        LongestPath { cost, nodes }
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Eq + Hash,
+{
+    // Searches for an augmenting path from the unmatched `start`. Every node
+    // reached is a descendant of the node it was reached from, and nodes
+    // already reached in this search are skipped along with their
+    // descendants, which were queued when they were first reached.
+    fn augment<'g>(&'g self, start: &'g N, matches: &mut Matches<'g, N>) {
+        let mut parents: HashMap<&N, &N> = HashMap::new();
+        let mut stack: Vec<_> = self.graph[start]
+            .out_neighbors
+            .iter()
+            .map(|out_neighbor| (out_neighbor, start))
+            .collect();
+
+        while let Some((node, parent)) = stack.pop() {
+            if parents.contains_key(node) {
+                continue;
+            }
+
+            parents.insert(node, parent);
+            let Some(&matched) = matches.right.get(node) else {
+                let mut node = node;
+                while let Some(previous) = matches.insert(parents[node], node) {
+                    node = previous;
+                }
+
+                return;
+            };
+
+            for (from, parent) in [(node, parent), (matched, matched)] {
+                let out_neighbors = &self.graph[from].out_neighbors;
+                stack.extend(
+                    out_neighbors
+                        .iter()
+                        .map(|out_neighbor| (out_neighbor, parent)),
+                );
+            }
+        }
+    }
+}
+
+// A matching between nodes on the left and their descendants on the right.
+struct Matches<'g, N> {
+    left: HashMap<&'g N, &'g N>,
+    right: HashMap<&'g N, &'g N>,
+}
+
+impl<N> Default for Matches<'_, N> {
+    fn default() -> Self {
+        Self {
+            left: HashMap::new(),
+            right: HashMap::new(),
+        }
+    }
+}
+
+impl<'g, N> Matches<'g, N>
+where
+    N: Eq + Hash,
+{
+    // Returns the node `left` was matched with before.
+    fn insert(&mut self, left: &'g N, right: &'g N) -> Option<&'g N> {
+        self.right.insert(right, left);
+        self.left.insert(left, right)
+    }
+}
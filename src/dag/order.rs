@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::NodeData;
+use super::topological_sort;
+
+// A topological order kept up to date as edges are added, following Pearce and
+// Kelly's dynamic topological sort: adding an edge only reorders the nodes
+// whose positions lie between its endpoints.
+#[derive(Clone, Debug)]
+pub(super) struct Order<N> {
+    positions: HashMap<N, isize>,
+    // Positions grow downwards from `first` for new sources, and upwards from
+    // `next` for everything else.
+    first: isize,
+    next: isize,
+}
+
+impl<N> Order<N> {
+    pub fn new() -> Self {
+        Self {
+            positions: HashMap::new(),
+            first: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<N> Order<N>
+where
+    N: Eq + Hash,
+{
+    pub fn remove(&mut self, node: &N) {
+        self.positions.remove(node);
+    }
+}
+
+impl<N> Order<N>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn from_nodes<'n, I>(nodes: I) -> Self
+    where
+        I: IntoIterator<Item = &'n N>,
+        N: 'n,
+    {
+        let positions: HashMap<_, _> = nodes
+            .into_iter()
+            .enumerate()
+            .map(|(position, node)| (node.clone(), position as isize))
+            .collect();
+
+        let next = positions.len() as isize;
+        Self {
+            positions,
+            first: 0,
+            next,
+        }
+    }
+
+    pub fn from_graph<V, E>(graph: &HashMap<N, NodeData<N, V, E>>) -> Option<Self> {
+        topological_sort(graph).map(Self::from_nodes)
+    }
+
+    // Nodes without edges can take any position, so they are only placed once
+    // they get one: first if it is an out-edge, so that prepending to a chain
+    // never reorders it, and last otherwise.
+    pub fn insert_edge(&mut self, from: &N, to: &N) {
+        if !self.positions.contains_key(from) {
+            self.first -= 1;
+            self.positions.insert(from.clone(), self.first);
+        }
+
+        if !self.positions.contains_key(to) {
+            self.positions.insert(to.clone(), self.next);
+            self.next += 1;
+        }
+    }
+
+    pub fn is_ordered(&self, from: &N, to: &N) -> bool {
+        self.positions[from] < self.positions[to]
+    }
+
+    /// Moves nodes so that `from` precedes `to`, before the edge is added to
+    /// `graph`. Fails with the path from `to` back to `from` if there is one.
+    pub fn add_edge<V, E>(
+        &mut self,
+        graph: &HashMap<N, NodeData<N, V, E>>,
+        from: &N,
+        to: &N,
+    ) -> Result<(), Vec<N>> {
+        self.insert_edge(from, to);
+
+        let lower_bound = self.positions[to];
+        let upper_bound = self.positions[from];
+        if upper_bound < lower_bound {
+            return Ok(());
+        }
+
+        let forward = search(
+            graph,
+            to,
+            |data| &data.out_neighbors,
+            |node| self.positions[node] <= upper_bound,
+        );
+
+        if let Some(path) = path(&forward, to, from) {
+            return Err(path);
+        }
+
+        let backward = search(
+            graph,
+            from,
+            |data| &data.in_neighbors,
+            |node| self.positions[node] >= lower_bound,
+        );
+
+        let mut backward: Vec<_> = backward.into_keys().collect();
+        let mut forward: Vec<_> = forward.into_keys().collect();
+        backward.sort_by_key(|node| self.positions[*node]);
+        forward.sort_by_key(|node| self.positions[*node]);
+
+        let mut positions: Vec<_> = backward
+            .iter()
+            .chain(&forward)
+            .map(|node| self.positions[*node])
+            .collect();
+
+        positions.sort_unstable();
+
+        for (node, position) in backward.into_iter().chain(forward).zip(positions) {
+            *self.positions.get_mut(node).unwrap() = position;
+        }
+
+        Ok(())
+    }
+}
+
+/// Any path from `to` back to `from` in a graph that may already have cycles.
+pub(super) fn find_path<N, V, E>(
+    graph: &HashMap<N, NodeData<N, V, E>>,
+    from: &N,
+    to: &N,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+{
+    path(
+        &search(graph, to, |data| &data.out_neighbors, |_| true),
+        to,
+        from,
+    )
+}
+
+// Maps every node reached from `start` through nodes accepted by `visit` to the
+// node it was first reached from.
+fn search<'g, N, V, E, F, P>(
+    graph: &'g HashMap<N, NodeData<N, V, E>>,
+    start: &'g N,
+    neighbors: F,
+    visit: P,
+) -> HashMap<&'g N, Option<&'g N>>
+where
+    N: Eq + Hash,
+    F: Fn(&'g NodeData<N, V, E>) -> &'g Vec<N>,
+    P: Fn(&N) -> bool,
+{
+    let mut parents = HashMap::from([(start, None)]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        for neighbor in graph.get(node).map(&neighbors).into_iter().flatten() {
+            if !parents.contains_key(neighbor) && visit(neighbor) {
+                parents.insert(neighbor, Some(node));
+                stack.push(neighbor);
+            }
+        }
+    }
+
+    parents
+}
+
+fn path<N>(parents: &HashMap<&N, Option<&N>>, start: &N, end: &N) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+{
+    let mut node = end;
+    let mut path = vec![end.clone()];
+
+    parents.get(end)?;
+    while node != start {
+        node = parents[node]?;
+        path.push(node.clone());
+    }
+
+    path.reverse();
+    Some(path)
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use super::Dag;
+use super::Edge;
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+{
+    /// Edges whose target is also reachable through a longer path, in no
+    /// particular order.
+    pub fn redundant_edges(&self) -> Vec<Edge<N>> {
+        let descendants = self.descendants();
+        let mut redundant_edges = Vec::new();
+
+        for node in self.order.iter() {
+            let out_neighbors = &self.graph[node].out_neighbors;
+            let indirect: HashSet<_> = out_neighbors
+                .iter()
+                .flat_map(|out_neighbor| &descendants[out_neighbor])
+                .collect();
+
+            for out_neighbor in out_neighbors {
+                if indirect.contains(&out_neighbor) {
+                    redundant_edges.push(Edge::new(node.clone(), out_neighbor.clone()));
+                }
+            }
+        }
+
+        redundant_edges
+    }
+}
+
+impl<N, V, E> Dag<N, V, E>
+where
+    N: Clone + Eq + Hash,
+    V: Clone,
+    E: Clone,
+{
+    /// The DAG with the same reachability and as few edges as possible.
+    pub fn transitive_reduction(&self) -> Dag<N, V, E> {
+        let mut builder = self.clone().into_builder();
+        for edge in self.redundant_edges() {
+            builder.remove_edge(&edge);
+        }
+
+        Dag {
+            graph: builder.graph.into(),
+            order: self.order.clone(),
+        }
+    }
+}
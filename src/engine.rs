@@ -1,40 +1,171 @@
+use std::any::Any;
 use std::collections::HashMap;
-use std::collections::VecDeque;
+use std::collections::HashSet;
+use std::collections::hash_map::Entry;
+use std::fmt;
 use std::hash::Hash;
+use std::io;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::sync::RwLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
 
 use futures::FutureExt;
 use futures::StreamExt;
+use futures::future;
+use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
 
 use crate::context::Context;
+use crate::dag::BuildDagError;
+use crate::dag::Dag;
+use crate::dag::DagBuilder;
+use crate::dag::DagDiff;
+use crate::dag::Edge;
+use crate::task;
+use crate::task::Closer;
 use crate::task::DynTask;
+use crate::task::Input;
+use crate::task::Scope;
 use crate::task::Task;
 
-mod dag;
-use dag::BuildDagError;
-use dag::Dag;
-use dag::Edge;
-use dag::NodeData;
+mod blocking;
+pub use blocking::Blocking;
 
+mod cache;
+pub use cache::CacheStatus;
+pub use cache::LruCache;
+pub use cache::TaskCache;
+
+mod checkpoint;
+use checkpoint::Checkpoint;
+pub use checkpoint::CheckpointStore;
+pub use checkpoint::FsCheckpointStore;
+
+mod incremental;
+use incremental::Cutoff;
+
+mod metrics;
+pub use metrics::Metrics;
+
+mod progress;
+pub use progress::Observer;
+pub(crate) use progress::Parent;
+pub use progress::Progress;
+pub(crate) use progress::Reporter;
+pub use progress::TaskProgress;
+pub use progress::TaskStatus;
+
+mod resources;
+pub use resources::ResourceId;
+pub(crate) use resources::Resources;
+
+mod rewired;
+use rewired::Rewired;
+
+#[cfg(feature = "serde")]
+mod spec;
+#[cfg(feature = "serde")]
+pub use spec::Registry;
+#[cfg(feature = "serde")]
+pub use spec::TaskSpec;
+#[cfg(feature = "serde")]
+pub use spec::WorkflowSpec;
+
+mod spawner;
+#[cfg(feature = "async-std")]
+pub use spawner::AsyncStdSpawner;
+pub use spawner::Spawner;
+pub use spawner::ThreadPool;
+#[cfg(feature = "tokio")]
+pub use spawner::TokioSpawner;
+
+mod stats;
+pub use stats::Histogram;
+pub use stats::Report;
+pub use stats::TaskReport;
+
+mod sub_engine;
+pub use sub_engine::SubEngine;
+
+/// Tasks are shared by every run of an engine and only see `&self`, so any
+/// per-run state has to live in the `Context` or be derived from the `Scope`.
 #[derive(Clone)]
 pub struct Engine<'a, I, D> {
-    dag: Dag<I>,
+    dag: Dag<I, (), EdgeKind>,
     tasks: Arc<HashMap<I, Arc<DynTask<'a, I, D>>>>,
+    runs: Arc<AtomicU64>,
+    stats: Arc<HashMap<I, Arc<Mutex<Histogram>>>>,
+    cache: Option<Arc<dyn TaskCache<I, D> + 'a>>,
+    observer: Option<Arc<dyn Observer<I>>>,
+    resources: Arc<Resources>,
+    spawner: Option<Arc<dyn Spawner>>,
+    metrics: Arc<OnceLock<Metrics<I>>>,
 }
 
 impl<'a, I, D> Engine<'a, I, D> {
     pub fn new() -> Self {
         Self {
-            dag: Dag::new(),
+            dag: Dag::default(),
             tasks: Arc::new(HashMap::new()),
+            runs: Arc::new(AtomicU64::new(0)),
+            stats: Arc::new(HashMap::new()),
+            cache: None,
+            observer: None,
+            resources: Arc::new(Resources::new()),
+            spawner: None,
+            metrics: Arc::new(OnceLock::new()),
         }
     }
 
     pub fn builder() -> EngineBuilder<'a, I, D> {
         EngineBuilder::new()
     }
+
+    pub fn runs(&self) -> u64 {
+        self.runs.load(Ordering::Relaxed)
+    }
+
+    pub fn dag(&self) -> &Dag<I, (), EdgeKind> {
+        &self.dag
+    }
+}
+
+impl<I, D> Engine<'_, I, D>
+where
+    I: Clone + Eq + Hash,
+{
+    /// Computed on first use, since finding the width takes time quadratic in
+    /// the number of tasks.
+    pub fn metrics(&self) -> &Metrics<I> {
+        self.metrics.get_or_init(|| {
+            let inputs = self
+                .dag
+                .nodes()
+                .filter(|id| !self.tasks.contains_key(id))
+                .cloned()
+                .collect();
+
+            Metrics::from_dag(&self.dag, &inputs, |id| self.tasks[id].cost())
+        })
+    }
+
+    /// Latencies of each task over every run, measured as in `TaskReport`.
+    pub fn stats(&self) -> HashMap<I, Histogram> {
+        self.stats
+            .iter()
+            .map(|(id, histogram)| (id.clone(), histogram.lock().unwrap().clone()))
+            .collect()
+    }
+
+    /// The changes to the graph of tasks and inputs from `self` to `other`,
+    /// where an edge that turns from data into ordering, or back, is changed.
+    pub fn diff(&self, other: &Engine<'_, I, D>) -> DagDiff<I> {
+        self.dag.diff(&other.dag)
+    }
 }
 
 impl<I, D> Default for Engine<'_, I, D> {
@@ -49,50 +180,288 @@ where
     I: Clone + Eq + Hash + Send + 'cx,
     D: Clone + Send + Sync + 'cx,
 {
-    pub async fn run(&self, context: Context<'cx, I, Option<D>>) {
+    /// Tasks whose ids already have values in `context` are not run, and the
+    /// existing values are passed to their dependents instead.
+    pub async fn run(&self, context: Context<'cx, I, Option<D>>) -> Report<I> {
+        self.execute(context, Options::default()).await
+    }
+
+    /// Like `run`, but tracks the tasks in `progress`, which can be read from
+    /// elsewhere while the run is pending.
+    pub async fn run_with_progress(
+        &self,
+        context: Context<'cx, I, Option<D>>,
+        progress: &Progress<I>,
+    ) -> Report<I> {
+        self.execute(
+            context,
+            Options {
+                progress: Some(progress),
+                ..Options::default()
+            },
+        )
+        .await
+    }
+
+    /// Loads the outputs saved in `store` into `context`, runs the remaining
+    /// tasks and saves their outputs as they complete.
+    pub async fn resume<S>(
+        &self,
+        context: Context<'cx, I, Option<D>>,
+        store: S,
+    ) -> io::Result<Report<I>>
+    where
+        S: CheckpointStore<I, D> + 'cx,
+    {
+        let checkpoint = Checkpoint::new(store);
+        for id in self.tasks.keys() {
+            if context.get(id).is_some() {
+                continue;
+            }
+
+            if let Some(output) = checkpoint.load(id)? {
+                context.set(id.clone(), future::ready(output).boxed().shared());
+            }
+        }
+
+        let report = self
+            .execute(
+                context,
+                Options {
+                    checkpoint: Some(checkpoint.clone()),
+                    ..Options::default()
+                },
+            )
+            .await;
+        match checkpoint.take_error() {
+            Some(err) => Err(err),
+            None => Ok(report),
+        }
+    }
+
+    /// Reuses the values in `previous` for everything except `changed` and its
+    /// transitive dependents, which are run again. New values for changed
+    /// external inputs should be seeded in `context` beforehand.
+    pub async fn recompute(
+        &self,
+        context: Context<'cx, I, Option<D>>,
+        previous: &Context<'cx, I, Option<D>>,
+        changed: &[I],
+    ) -> Report<I> {
+        self.reuse(&context, previous, &self.dependents(changed));
+        self.execute(context, Options::default()).await
+    }
+
+    /// Like `recompute`, but a dependent whose recomputed inputs are all equal
+    /// to their previous values reuses its previous output instead of running.
+    pub async fn recompute_with_cutoff(
+        &self,
+        context: Context<'cx, I, Option<D>>,
+        previous: &Context<'cx, I, Option<D>>,
+        changed: &[I],
+    ) -> Report<I>
+    where
+        D: PartialEq,
+    {
+        let dirty = self.dependents(changed);
+        self.reuse(&context, previous, &dirty);
+
+        let cutoff = Cutoff::new(
+            previous.clone(),
+            changed.iter().cloned().collect(),
+            dirty,
+            PartialEq::eq,
+        );
+
+        self.execute(
+            context,
+            Options {
+                cutoff: Some(&cutoff),
+                ..Options::default()
+            },
+        )
+        .await
+    }
+
+    fn is_data_edge(&self, from: &I, to: &I) -> bool {
+        self.dag.edge_value(from, to) != Some(&EdgeKind::Ordering)
+    }
+
+    fn dependents(&self, changed: &[I]) -> HashSet<I> {
         let graph = self.dag.graph();
-        let mut in_degrees: HashMap<_, _> = graph
-            .iter()
-            .map(|(node, NodeData { in_neighbors, .. })| (node, in_neighbors.len()))
-            .collect();
+        let mut dependents = HashSet::new();
+        let mut stack: Vec<_> = changed.iter().filter(|id| graph.contains_key(id)).collect();
 
-        let mut queue: VecDeque<_> = in_degrees
-            .iter()
-            .flat_map(|(&node, &in_degree)| if in_degree > 0 { None } else { Some(node) })
-            .collect();
+        while let Some(node) = stack.pop() {
+            if dependents.insert(node.clone()) {
+                stack.extend(&graph[node].out_neighbors);
+            }
+        }
+
+        dependents
+    }
+
+    fn reuse(
+        &self,
+        context: &Context<'cx, I, Option<D>>,
+        previous: &Context<'cx, I, Option<D>>,
+        dirty: &HashSet<I>,
+    ) {
+        for node in self.dag.graph().keys() {
+            if dirty.contains(node) || context.get(node).is_some() {
+                continue;
+            }
+
+            if let Some(value) = previous.get(node) {
+                context.set(node.clone(), value);
+            }
+        }
+    }
+
+    async fn execute(
+        &self,
+        context: Context<'cx, I, Option<D>>,
+        options: Options<'_, 'cx, I, D>,
+    ) -> Report<I> {
+        let Options {
+            checkpoint,
+            cutoff,
+            progress,
+            spawn,
+            parent,
+        } = options;
 
-        while let Some(node) = queue.pop_front() {
-            if let Some(task) = self.tasks.get(node).cloned() {
-                let inputs = graph[node]
+        let run_id = self.runs.fetch_add(1, Ordering::Relaxed);
+        let reporter =
+            Reporter::new(run_id, self.observer.clone(), progress.cloned()).with_parent(parent);
+
+        if let Some(progress) = progress {
+            for (id, task) in self.tasks.iter() {
+                let status = match context.get(id) {
+                    Some(_) => TaskStatus::Done,
+                    None => TaskStatus::Pending,
+                };
+
+                progress.set(id.clone(), task.cost(), status);
+            }
+        }
+
+        let cache = self
+            .cache
+            .clone()
+            .filter(|_| context.shared_params().is_none());
+        let graph = self.dag.graph();
+        let mut subscriptions: HashMap<_, HashMap<_, _>> = HashMap::new();
+
+        for node in self.dag.topological_order() {
+            let streams = subscriptions.remove(node).unwrap_or_default();
+            if let Some(task) = self.tasks.get(node).cloned()
+                && context.get(node).is_none()
+            {
+                let (dependencies, after): (Vec<_>, Vec<_>) = graph[node]
                     .in_neighbors
                     .iter()
-                    .flat_map(|in_neighbor| {
+                    .cloned()
+                    .partition(|in_neighbor| self.is_data_edge(in_neighbor, node));
+
+                let inputs = dependencies
+                    .iter()
+                    .flat_map(|dependency| {
                         context
-                            .get(in_neighbor)
-                            .map(|data| (in_neighbor.clone(), data))
+                            .get(dependency)
+                            .map(|data| (dependency.clone(), data))
                     })
                     .collect();
 
-                context.set(
-                    node.clone(),
-                    async move { task.run(inputs).await }.boxed().shared(),
-                );
-            }
+                let guard =
+                    cutoff.and_then(|cutoff| cutoff.guard(node, &dependencies, &after, &context));
+                let after: Vec<_> = after.iter().flat_map(|id| context.get(id)).collect();
+                let out_neighbors: Vec<_> = graph[node]
+                    .out_neighbors
+                    .iter()
+                    .filter(|out_neighbor| self.is_data_edge(node, out_neighbor))
+                    .collect();
+
+                let publisher = task.is_streaming().then(|| {
+                    let (publisher, streams) =
+                        task::channel(out_neighbors.len(), task.stream_capacity());
+
+                    for (&out_neighbor, stream) in out_neighbors.iter().zip(streams) {
+                        subscriptions
+                            .entry(out_neighbor)
+                            .or_default()
+                            .insert(node.clone(), stream);
+                    }
+
+                    publisher
+                });
+
+                let closer = publisher.clone().map(Closer);
+                let id = node.clone();
+                let reporter = reporter.clone();
+                let scope = Scope::new(id.clone(), run_id)
+                    .with_streams(publisher, streams)
+                    .with_reporter(reporter.clone())
+                    .with_resources(self.resources.clone())
+                    .with_params(context.shared_params())
+                    .with_spawner(self.spawner.clone());
+                let histogram = self.stats.get(&id).cloned();
+                let cache = cache.clone().filter(|_| task.is_cacheable());
+                let checkpoint = checkpoint.clone();
+
+                let future = async move {
+                    let _closer = closer;
+                    future::join_all(after).await;
+                    if let Some(guard) = guard
+                        && let Some(data) = guard.check().await
+                    {
+                        reporter.skip(&id);
+                        return data;
+                    }
 
-            for out_neighbor in &graph[node].out_neighbors {
-                let in_degree = in_degrees.get_mut(out_neighbor).unwrap();
-                *in_degree -= 1;
+                    reporter.start(&id);
+                    let now = Instant::now();
+                    let (data, cache) = match cache {
+                        Some(cache) => {
+                            let (data, status) =
+                                run_cached(&*cache, id.clone(), &*task, inputs, scope).await;
+                            (data, Some(status))
+                        }
+                        None => (task.run(inputs, scope).await, None),
+                    };
 
-                if *in_degree == 0 {
-                    queue.push_back(out_neighbor);
+                    let latency = now.elapsed();
+                    if let Some(histogram) = histogram {
+                        histogram.lock().unwrap().record(latency);
+                    }
+
+                    if let Some(checkpoint) = checkpoint {
+                        checkpoint.save(&id, &data);
+                    }
+
+                    let report = TaskReport { latency, cache };
+                    reporter.finish(id, report);
+
+                    data
                 }
+                .boxed();
+
+                let future = match spawn {
+                    Some(spawn) => spawn(future),
+                    None => future,
+                };
+
+                context.set(node.clone(), future.shared());
             }
         }
 
         graph
-            .iter()
-            .flat_map(|(node, _)| {
-                if self.tasks.get(node)?.is_auto() {
+            .keys()
+            .flat_map(|node| {
+                // Reading a subscription does not poll its producer, so
+                // streaming tasks always run.
+                let task = self.tasks.get(node)?;
+                if task.is_auto() || task.is_streaming() {
                     context.get(node)
                 } else {
                     None
@@ -101,21 +470,140 @@ where
             .collect::<FuturesUnordered<_>>()
             .collect::<Vec<_>>()
             .await;
+
+        Report {
+            run_id,
+            tasks: reporter.reports(),
+        }
+    }
+}
+
+impl<I, D> Engine<'static, I, D>
+where
+    I: Clone + Eq + Hash + Send + Sync + 'static,
+    D: Clone + Send + Sync + 'static,
+{
+    /// Like `run`, but each task is spawned on the engine's `Spawner` when it is
+    /// first awaited, so independent tasks run in parallel while tasks that are
+    /// not auto still only run once a dependent awaits them. Runs everything on
+    /// the calling task if the engine has no spawner.
+    pub async fn run_parallel(&self, context: Context<'static, I, Option<D>>) -> Report<I> {
+        let Some(spawner) = self.spawner.clone() else {
+            return self.run(context).await;
+        };
+
+        let spawn = move |future: BoxFuture<'static, Option<D>>| {
+            let spawner = spawner.clone();
+            async move {
+                let (future, handle) = future.remote_handle();
+                spawner.spawn(future.boxed());
+                handle.await
+            }
+            .boxed()
+        };
+
+        self.execute(
+            context,
+            Options {
+                spawn: Some(&spawn),
+                ..Options::default()
+            },
+        )
+        .await
+    }
+}
+
+type Spawn<'a, D> = dyn Fn(BoxFuture<'a, Option<D>>) -> BoxFuture<'a, Option<D>> + Send + Sync;
+
+struct Options<'o, 'cx, I, D> {
+    checkpoint: Option<Checkpoint<'cx, I, D>>,
+    cutoff: Option<&'o Cutoff<'cx, I, D>>,
+    progress: Option<&'o Progress<I>>,
+    spawn: Option<&'o Spawn<'cx, D>>,
+    parent: Option<Parent<I>>,
+}
+
+impl<I, D> Default for Options<'_, '_, I, D> {
+    fn default() -> Self {
+        Self {
+            checkpoint: None,
+            cutoff: None,
+            progress: None,
+            spawn: None,
+            parent: None,
+        }
     }
 }
 
+async fn run_cached<I, D>(
+    cache: &dyn TaskCache<I, D>,
+    id: I,
+    task: &DynTask<'_, I, D>,
+    inputs: HashMap<I, Input<'_, D>>,
+    scope: Scope<I, D>,
+) -> (Option<D>, CacheStatus)
+where
+    I: Clone + Eq + Hash + Send,
+    D: Clone + Send + Sync,
+{
+    let outputs = inputs
+        .iter()
+        .map(|(id, input)| {
+            let id = id.clone();
+            input.clone().map(move |output| (id, output))
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect()
+        .await;
+
+    let fingerprint = cache.fingerprint(&outputs);
+    if let Some(data) = cache.get(&id, fingerprint) {
+        return (data, CacheStatus::Hit);
+    }
+
+    let data = task.run(inputs, scope).await;
+    cache.insert(id, fingerprint, data.clone());
+
+    (data, CacheStatus::Miss)
+}
+
 #[derive(Clone)]
 pub struct EngineBuilder<'a, I, D> {
-    #[allow(clippy::type_complexity)]
-    tasks: Arc<RwLock<HashMap<I, Box<DynTask<'a, I, D>>>>>,
+    inner: Arc<RwLock<Inner<'a, I, D>>>,
 }
 
-impl<I, D> EngineBuilder<'_, I, D> {
+struct Inner<'a, I, D> {
+    tasks: HashMap<I, Box<DynTask<'a, I, D>>>,
+    inputs: HashSet<I>,
+    duplicates: Vec<I>,
+    cache: Option<Arc<dyn TaskCache<I, D> + 'a>>,
+    observer: Option<Arc<dyn Observer<I>>>,
+    resources: Resources,
+    spawner: Option<Arc<dyn Spawner>>,
+}
+
+impl<'a, I, D> EngineBuilder<'a, I, D> {
     pub fn new() -> Self {
+        Self::from_inner(Inner {
+            tasks: HashMap::new(),
+            inputs: HashSet::new(),
+            duplicates: Vec::new(),
+            cache: None,
+            observer: None,
+            resources: Resources::new(),
+            spawner: None,
+        })
+    }
+
+    fn from_inner(inner: Inner<'a, I, D>) -> Self {
         Self {
-            tasks: Arc::new(RwLock::new(HashMap::new())),
+            inner: Arc::new(RwLock::new(inner)),
         }
     }
+
+    fn into_inner(self) -> Inner<'a, I, D> {
+        Arc::into_inner(self.inner).unwrap().into_inner().unwrap()
+    }
 }
 
 impl<I, D> Default for EngineBuilder<'_, I, D> {
@@ -136,7 +624,7 @@ where
     }
 
     pub fn exists_task_by_id(&self, id: &I) -> bool {
-        self.tasks.read().unwrap().contains_key(id)
+        self.inner.read().unwrap().tasks.contains_key(id)
     }
 
     pub fn remove_task<T>(&self, task: &T) -> &Self
@@ -146,8 +634,27 @@ where
         self.remove_task_by_id(&task.id())
     }
 
+    /// Also forgets any duplicates of `id` added with `add_task`.
     pub fn remove_task_by_id(&self, id: &I) -> &Self {
-        self.tasks.write().unwrap().remove(id);
+        let mut inner = self.inner.write().unwrap();
+        inner.tasks.remove(id);
+        inner.duplicates.retain(|duplicate| duplicate != id);
+
+        drop(inner);
+        self
+    }
+
+    pub fn add_input(&self, id: I) -> &Self {
+        self.inner.write().unwrap().inputs.insert(id);
+        self
+    }
+
+    pub fn exists_input(&self, id: &I) -> bool {
+        self.inner.read().unwrap().inputs.contains(id)
+    }
+
+    pub fn remove_input(&self, id: &I) -> &Self {
+        self.inner.write().unwrap().inputs.remove(id);
         self
     }
 }
@@ -160,11 +667,92 @@ where
     where
         T: Task<I, D> + 'a,
     {
-        self.tasks
-            .write()
-            .unwrap()
-            .insert(task.id(), DynTask::new_box(task));
+        let mut inner = self.inner.write().unwrap();
+        let Inner {
+            tasks, duplicates, ..
+        } = &mut *inner;
 
+        match tasks.entry(task.id()) {
+            Entry::Occupied(_) => {
+                let id = task.id();
+                if !duplicates.contains(&id) {
+                    duplicates.push(id);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(DynTask::new_box(task));
+            }
+        }
+
+        drop(inner);
+        self
+    }
+
+    /// Like `add_task`, but hands `task` back if its id is taken.
+    pub fn try_add_task<T>(&self, task: T) -> Result<&Self, AddTaskError<I, T>>
+    where
+        T: Task<I, D> + 'a,
+    {
+        match self.inner.write().unwrap().tasks.entry(task.id()) {
+            Entry::Occupied(_) => Err(AddTaskError {
+                id: task.id(),
+                task,
+            })?,
+            Entry::Vacant(entry) => {
+                entry.insert(DynTask::new_box(task));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Caches the outputs of cacheable tasks by their inputs. Runs whose
+    /// context has params bypass the cache, since params cannot be hashed.
+    pub fn cache<C>(&self, cache: C) -> &Self
+    where
+        C: TaskCache<I, D> + 'a,
+    {
+        self.inner.write().unwrap().cache = Some(Arc::new(cache));
+        self
+    }
+
+    pub fn observer<O>(&self, observer: O) -> &Self
+    where
+        O: Observer<I> + 'static,
+    {
+        self.inner.write().unwrap().observer = Some(Arc::new(observer));
+        self
+    }
+
+    pub fn spawner<S>(&self, spawner: S) -> &Self
+    where
+        S: Spawner + 'static,
+    {
+        self.inner.write().unwrap().spawner = Some(Arc::new(spawner));
+        self
+    }
+
+    /// Registers a resource shared by every task, replacing any previous
+    /// resource of the same type.
+    pub fn resource<T>(&self, resource: T) -> &Self
+    where
+        T: Any + Send + Sync,
+    {
+        self.inner.write().unwrap().resources.insert(resource);
+        self
+    }
+
+    /// Replaces the task with the same id, and any duplicates of it.
+    pub fn replace_task<T>(&self, task: T) -> &Self
+    where
+        T: Task<I, D> + 'a,
+    {
+        let mut inner = self.inner.write().unwrap();
+        let id = task.id();
+        inner.duplicates.retain(|duplicate| *duplicate != id);
+        inner.tasks.insert(id, DynTask::new_box(task));
+
+        drop(inner);
         self
     }
 }
@@ -173,38 +761,317 @@ impl<'a, I, D> EngineBuilder<'a, I, D>
 where
     I: Clone + Eq + Hash,
 {
-    pub fn build(self) -> Result<Engine<'a, I, D>, BuildEngineError> {
-        let tasks = Arc::into_inner(self.tasks).unwrap().into_inner().unwrap();
-        let mut builder = Dag::builder();
+    pub fn merge(
+        &self,
+        other: EngineBuilder<'a, I, D>,
+        conflict: Conflict,
+    ) -> Result<&Self, BuildEngineError<I>> {
+        let other = other.into_inner();
+        let mut inner = self.inner.write().unwrap();
 
-        for id in tasks.keys().cloned() {
-            builder.add_node(id);
+        if conflict == Conflict::Error {
+            let duplicates: Vec<_> = other
+                .tasks
+                .keys()
+                .filter(|id| inner.tasks.contains_key(id))
+                .cloned()
+                .collect();
+
+            if !duplicates.is_empty() {
+                Err(EngineErrorKind::DuplicateTasks(duplicates))?
+            }
+        }
+
+        for (id, task) in other.tasks {
+            if conflict == Conflict::Keep && inner.tasks.contains_key(&id) {
+                continue;
+            }
+
+            inner.tasks.insert(id, task);
+        }
+
+        inner.inputs.extend(other.inputs);
+        if inner.cache.is_none() {
+            inner.cache = other.cache;
+        }
+
+        if inner.observer.is_none() {
+            inner.observer = other.observer;
+        }
+
+        inner.resources.extend(other.resources);
+        if inner.spawner.is_none() {
+            inner.spawner = other.spawner;
         }
 
-        for (id, task) in &tasks {
-            for dependency in task.dependencies() {
-                builder.add_edge(Edge::new(dependency, id.clone()));
+        for id in other.duplicates {
+            if !inner.duplicates.contains(&id) {
+                inner.duplicates.push(id);
             }
         }
 
+        drop(inner);
+        Ok(self)
+    }
+}
+
+impl<'a, I, D> EngineBuilder<'a, I, D>
+where
+    I: Clone + Eq + Hash + Send + Sync + 'a,
+    D: Send + Sync + 'a,
+{
+    pub fn namespace<F>(self, namespace: F) -> Self
+    where
+        F: Fn(&I) -> I,
+    {
+        let Inner {
+            tasks,
+            inputs,
+            duplicates,
+            cache,
+            observer,
+            resources,
+            spawner,
+        } = self.into_inner();
+
+        let ids: HashMap<_, _> = tasks.keys().map(|id| (id.clone(), namespace(id))).collect();
+        let mut duplicates: Vec<_> = duplicates
+            .iter()
+            .map(|id| ids.get(id).cloned().unwrap_or_else(|| namespace(id)))
+            .collect();
+
+        // Tasks whose ids collide once namespaced are duplicates, like those
+        // added twice with `add_task`.
+        let mut namespaced = HashMap::with_capacity(tasks.len());
+        for (id, task) in tasks {
+            let id = ids[&id].clone();
+            match namespaced.entry(id.clone()) {
+                Entry::Occupied(_) => {
+                    if !duplicates.contains(&id) {
+                        duplicates.push(id);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(DynTask::new_box(Rewired::namespaced(id, task, &ids)));
+                }
+            }
+        }
+
+        Self::from_inner(Inner {
+            tasks: namespaced,
+            inputs,
+            duplicates,
+            cache,
+            observer,
+            resources,
+            spawner,
+        })
+    }
+}
+
+impl<'a, I, D> EngineBuilder<'a, I, D>
+where
+    I: Clone + Eq + Hash,
+{
+    pub fn build(self) -> Result<Engine<'a, I, D>, BuildEngineError<I>> {
+        let Inner {
+            tasks,
+            inputs,
+            duplicates,
+            cache,
+            observer,
+            resources,
+            spawner,
+        } = self.into_inner();
+
+        if !duplicates.is_empty() {
+            Err(EngineErrorKind::DuplicateTasks(duplicates))?
+        }
+
+        let builder = Self::dag_builder(&tasks, &inputs)?;
+
+        let mut missing_resources = Vec::new();
+        for resource in tasks.values().flat_map(|task| task.resources()) {
+            if !resources.contains(&resource) && !missing_resources.contains(&resource) {
+                missing_resources.push(resource);
+            }
+        }
+
+        if !missing_resources.is_empty() {
+            Err(EngineErrorKind::MissingResources(missing_resources))?
+        }
+
         Ok(Engine {
             dag: builder.build().map_err(EngineErrorKind::DagBuildFailed)?,
+            runs: Arc::new(AtomicU64::new(0)),
+            stats: Arc::new(
+                tasks
+                    .keys()
+                    .map(|id| (id.clone(), Arc::new(Mutex::new(Histogram::new()))))
+                    .collect(),
+            ),
             tasks: Arc::new(
                 tasks
                     .into_iter()
                     .map(|(id, task)| (id, task.into()))
                     .collect(),
             ),
+            cache,
+            observer,
+            resources: Arc::new(resources),
+            spawner,
+            metrics: Arc::new(OnceLock::new()),
         })
     }
+
+    /// Declared dependencies that are also reached through other dependencies,
+    /// as edges from the dependency to the task. An `after` entry is redundant
+    /// if the task is reached any other way, but a data dependency only if it
+    /// is reached through other data dependencies, since it passes an input.
+    pub fn redundant_dependencies(&self) -> Result<Vec<Edge<I>>, BuildEngineError<I>> {
+        let inner = self.inner.read().unwrap();
+        let mut builder = Self::dag_builder(&inner.tasks, &inner.inputs)?;
+        let dag = builder
+            .clone()
+            .build()
+            .map_err(EngineErrorKind::DagBuildFailed)?;
+
+        let ordering_edges: HashSet<_> = dag
+            .edges()
+            .filter(|Edge { from, to }| dag.edge_value(from, to) == Some(&EdgeKind::Ordering))
+            .map(|Edge { from, to }| Edge::new(from.clone(), to.clone()))
+            .collect();
+
+        for edge in &ordering_edges {
+            builder.remove_edge(edge);
+        }
+
+        let data_dag = builder.build().map_err(EngineErrorKind::DagBuildFailed)?;
+        let mut redundant_edges = data_dag.redundant_edges();
+        redundant_edges.extend(
+            dag.redundant_edges()
+                .into_iter()
+                .filter(|edge| ordering_edges.contains(edge)),
+        );
+
+        Ok(redundant_edges)
+    }
+
+    fn dag_builder(
+        tasks: &HashMap<I, Box<DynTask<'a, I, D>>>,
+        inputs: &HashSet<I>,
+    ) -> Result<DagBuilder<I, (), EdgeKind>, BuildEngineError<I>> {
+        let mut unknown_dependencies = Vec::new();
+        let mut builder = DagBuilder::default();
+
+        for id in tasks.keys().chain(inputs).cloned() {
+            builder.add_node(id);
+        }
+
+        for (id, task) in tasks {
+            let after = task.after().into_iter().map(|id| (id, EdgeKind::Ordering));
+            let dependencies = task
+                .dependencies()
+                .into_iter()
+                .map(|id| (id, EdgeKind::Data));
+
+            // Data edges come last to take precedence over ordering ones.
+            for (dependency, kind) in after.chain(dependencies) {
+                if !tasks.contains_key(&dependency)
+                    && !inputs.contains(&dependency)
+                    && !unknown_dependencies.contains(&dependency)
+                {
+                    unknown_dependencies.push(dependency.clone());
+                }
+
+                builder.add_edge_with(Edge::new(dependency, id.clone()), kind);
+            }
+        }
+
+        if !unknown_dependencies.is_empty() {
+            Err(EngineErrorKind::UnknownDependencies(unknown_dependencies))?
+        }
+
+        Ok(builder)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
+/// The value of each edge in `Engine::dag`, from a dependency to its dependent.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum EdgeKind {
+    /// The dependent gets the output of the dependency as an input.
+    #[default]
+    Data,
+    /// The dependent only starts after the dependency finishes, from
+    /// `Task::after`.
+    Ordering,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Conflict {
+    Error,
+    Replace,
+    Keep,
+}
+
+#[derive(thiserror::Error)]
+#[error("duplicate task id: {id:?}")]
+pub struct AddTaskError<I, T> {
+    id: I,
+    task: T,
+}
+
+impl<I, T> AddTaskError<I, T> {
+    pub fn id(&self) -> &I {
+        &self.id
+    }
+
+    /// The task that was rejected.
+    pub fn into_task(self) -> T {
+        self.task
+    }
+}
+
+impl<I, T> fmt::Debug for AddTaskError<I, T>
+where
+    I: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AddTaskError")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
 #[error(transparent)]
-pub struct BuildEngineError(#[from] EngineErrorKind);
+pub struct BuildEngineError<I>(#[from] EngineErrorKind<I>);
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, thiserror::Error)]
-enum EngineErrorKind {
+impl<I> BuildEngineError<I> {
+    /// The ids of tasks added more than once, if that is why building failed.
+    pub fn duplicate_tasks(&self) -> Option<&[I]> {
+        match &self.0 {
+            EngineErrorKind::DuplicateTasks(ids) => Some(ids),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+enum EngineErrorKind<I> {
     #[error("failed to build DAG")]
     DagBuildFailed(#[from] BuildDagError),
+
+    #[error("duplicate task ids: {0:?}")]
+    DuplicateTasks(Vec<I>),
+
+    #[error("unknown dependencies: {0:?}")]
+    UnknownDependencies(Vec<I>),
+
+    #[error("missing resources: {0:?}")]
+    MissingResources(Vec<ResourceId>),
+
+    #[cfg(feature = "serde")]
+    #[error("unknown task kinds: {0:?}")]
+    UnknownKinds(Vec<String>),
 }
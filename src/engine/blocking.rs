@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use futures::StreamExt;
+use futures::executor;
+use futures::future;
+use futures::stream::FuturesUnordered;
+
+use crate::engine::ResourceId;
+use crate::task::Input;
+use crate::task::Scope;
+use crate::task::Task;
+
+/// Runs a CPU-bound task with `Scope::spawn_blocking` on the engine's
+/// `Spawner`. All inputs are awaited before the task starts, including lazy
+/// ones the task would not have awaited. Without a spawner the task runs as
+/// it would unwrapped.
+pub struct Blocking<T> {
+    task: Arc<T>,
+}
+
+impl<T> Blocking<T> {
+    pub fn new(task: T) -> Self {
+        Self {
+            task: Arc::new(task),
+        }
+    }
+}
+
+impl<I, D, T> Task<I, D> for Blocking<T>
+where
+    I: Clone + Eq + Hash + Send + Sync + 'static,
+    D: Clone + Send + Sync + 'static,
+    T: Task<I, D> + 'static,
+{
+    fn id(&self) -> I {
+        self.task.id()
+    }
+
+    fn dependencies(&self) -> Vec<I> {
+        self.task.dependencies()
+    }
+
+    fn after(&self) -> Vec<I> {
+        self.task.after()
+    }
+
+    fn is_auto(&self) -> bool {
+        self.task.is_auto()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.task.is_cacheable()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.task.is_streaming()
+    }
+
+    fn stream_capacity(&self) -> usize {
+        self.task.stream_capacity()
+    }
+
+    fn resources(&self) -> Vec<ResourceId> {
+        self.task.resources()
+    }
+
+    fn cost(&self) -> u64 {
+        self.task.cost()
+    }
+
+    async fn run(&self, inputs: HashMap<I, Input<'_, D>>, scope: Scope<I, D>) -> Option<D> {
+        // Blocking on the task in place would nest executors.
+        if !scope.has_spawner() {
+            return self.task.run(inputs, scope).await;
+        }
+
+        let outputs: HashMap<_, _> = inputs
+            .into_iter()
+            .map(|(id, input)| input.map(move |output| (id, output)))
+            .collect::<FuturesUnordered<_>>()
+            .collect()
+            .await;
+
+        let task = self.task.clone();
+        let blocking_scope = scope.clone();
+
+        scope
+            .spawn_blocking(move || {
+                let inputs = outputs
+                    .into_iter()
+                    .map(|(id, output)| (id, future::ready(output).boxed().shared()))
+                    .collect();
+
+                executor::block_on(task.run(inputs, blocking_scope))
+            })
+            .await
+    }
+}
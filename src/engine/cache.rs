@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Mutex;
+
+pub trait TaskCache<I, D>: Send + Sync {
+    fn fingerprint(&self, inputs: &HashMap<I, Option<D>>) -> u64;
+
+    fn get(&self, id: &I, fingerprint: u64) -> Option<Option<D>>;
+
+    fn insert(&self, id: I, fingerprint: u64, output: Option<D>);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+pub struct LruCache<I, D> {
+    capacity: usize,
+    inner: Mutex<Lru<I, D>>,
+}
+
+struct Lru<I, D> {
+    tick: u64,
+    entries: HashMap<(I, u64), (Option<D>, u64)>,
+    ticks: BTreeMap<u64, (I, u64)>,
+}
+
+impl<I, D> LruCache<I, D> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Lru {
+                tick: 0,
+                entries: HashMap::new(),
+                ticks: BTreeMap::new(),
+            }),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<I, D> TaskCache<I, D> for LruCache<I, D>
+where
+    I: Clone + Eq + Hash + Send,
+    D: Clone + Hash + Send,
+{
+    fn fingerprint(&self, inputs: &HashMap<I, Option<D>>) -> u64 {
+        let mut hashes: Vec<_> = inputs
+            .iter()
+            .map(|input| {
+                let mut hasher = DefaultHasher::new();
+                input.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+
+        hashes.sort_unstable();
+
+        let mut hasher = DefaultHasher::new();
+        hashes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn get(&self, id: &I, fingerprint: u64) -> Option<Option<D>> {
+        let mut inner = self.inner.lock().unwrap();
+        let Lru {
+            tick,
+            entries,
+            ticks,
+        } = &mut *inner;
+
+        let (output, last_tick) = entries.get_mut(&(id.clone(), fingerprint))?;
+        let key = ticks.remove(last_tick).unwrap();
+
+        *tick += 1;
+        *last_tick = *tick;
+        ticks.insert(*tick, key);
+
+        Some(output.clone())
+    }
+
+    fn insert(&self, id: I, fingerprint: u64, output: Option<D>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let Lru {
+            tick,
+            entries,
+            ticks,
+        } = &mut *inner;
+
+        *tick += 1;
+        let key = (id, fingerprint);
+
+        if let Some((_, last_tick)) = entries.insert(key.clone(), (output, *tick)) {
+            ticks.remove(&last_tick);
+        } else if entries.len() > self.capacity
+            && let Some((_, evicted)) = ticks.pop_first()
+        {
+            entries.remove(&evicted);
+        }
+
+        ticks.insert(*tick, key);
+    }
+}
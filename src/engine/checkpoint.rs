@@ -0,0 +1,141 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+pub trait CheckpointStore<I, D>: Send + Sync {
+    fn load(&self, id: &I) -> io::Result<Option<Option<D>>>;
+
+    fn save(&self, id: &I, output: &Option<D>) -> io::Result<()>;
+}
+
+type Encode<D> = Box<dyn Fn(&D) -> Vec<u8> + Send + Sync>;
+type Decode<D> = Box<dyn Fn(&[u8]) -> io::Result<D> + Send + Sync>;
+
+pub struct FsCheckpointStore<D> {
+    dir: PathBuf,
+    encode: Encode<D>,
+    decode: Decode<D>,
+}
+
+impl<D> FsCheckpointStore<D> {
+    pub fn new<E, F>(dir: impl Into<PathBuf>, encode: E, decode: F) -> io::Result<Self>
+    where
+        E: Fn(&D) -> Vec<u8> + Send + Sync + 'static,
+        F: Fn(&[u8]) -> io::Result<D> + Send + Sync + 'static,
+    {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            encode: Box::new(encode),
+            decode: Box::new(decode),
+        })
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "ckpt")
+            {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path<I>(&self, id: &I) -> PathBuf
+    where
+        I: Display,
+    {
+        let name: String = id
+            .to_string()
+            .bytes()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+
+        self.dir.join(name).with_extension("ckpt")
+    }
+}
+
+impl<I, D> CheckpointStore<I, D> for FsCheckpointStore<D>
+where
+    I: Display,
+{
+    fn load(&self, id: &I) -> io::Result<Option<Option<D>>> {
+        let bytes = match fs::read(self.path(id)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        match bytes.split_first() {
+            Some((0, [])) => Ok(Some(None)),
+            Some((1, bytes)) => Ok(Some(Some((self.decode)(bytes)?))),
+            _ => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "malformed checkpoint",
+            )),
+        }
+    }
+
+    fn save(&self, id: &I, output: &Option<D>) -> io::Result<()> {
+        let bytes = match output {
+            Some(output) => [&[1], &(self.encode)(output)[..]].concat(),
+            None => vec![0],
+        };
+
+        let path = self.path(id);
+        let tmp = path.with_extension("tmp");
+
+        fs::write(&tmp, bytes)?;
+        fs::rename(tmp, path)
+    }
+}
+
+pub(crate) struct Checkpoint<'a, I, D> {
+    store: Arc<dyn CheckpointStore<I, D> + 'a>,
+    error: Arc<Mutex<Option<io::Error>>>,
+}
+
+impl<'a, I, D> Checkpoint<'a, I, D> {
+    pub fn new<S>(store: S) -> Self
+    where
+        S: CheckpointStore<I, D> + 'a,
+    {
+        Self {
+            store: Arc::new(store),
+            error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn load(&self, id: &I) -> io::Result<Option<Option<D>>> {
+        self.store.load(id)
+    }
+
+    pub fn save(&self, id: &I, output: &Option<D>) {
+        if let Err(err) = self.store.save(id, output) {
+            self.error.lock().unwrap().get_or_insert(err);
+        }
+    }
+
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.error.lock().unwrap().take()
+    }
+}
+
+impl<I, D> Clone for Checkpoint<'_, I, D> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            error: self.error.clone(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::context::Context;
+use crate::context::Value;
+
+type Compare<D> = fn(&Option<D>, &Option<D>) -> bool;
+
+pub(crate) struct Cutoff<'cx, I, D> {
+    previous: Context<'cx, I, Option<D>>,
+    changed: HashSet<I>,
+    dirty: HashSet<I>,
+    eq: Compare<D>,
+}
+
+impl<'cx, I, D> Cutoff<'cx, I, D>
+where
+    I: Eq + Hash,
+{
+    pub fn new(
+        previous: Context<'cx, I, Option<D>>,
+        changed: HashSet<I>,
+        dirty: HashSet<I>,
+        eq: Compare<D>,
+    ) -> Self {
+        Self {
+            previous,
+            changed,
+            dirty,
+            eq,
+        }
+    }
+
+    /// A task ordered after a dirty one always runs again, since its output
+    /// may depend on side effects that `after` edges carry no data for.
+    pub fn guard(
+        &self,
+        node: &I,
+        dependencies: &[I],
+        after: &[I],
+        context: &Context<'cx, I, Option<D>>,
+    ) -> Option<Guard<'cx, D>> {
+        if !self.dirty.contains(node)
+            || self.changed.contains(node)
+            || after.iter().any(|id| self.dirty.contains(id))
+        {
+            return None;
+        }
+
+        let inputs = dependencies
+            .iter()
+            .filter(|dependency| self.dirty.contains(dependency))
+            .map(|dependency| Some((context.get(dependency)?, self.previous.get(dependency)?)))
+            .collect::<Option<_>>()?;
+
+        Some(Guard {
+            output: self.previous.get(node)?,
+            inputs,
+            eq: self.eq,
+        })
+    }
+}
+
+pub(crate) struct Guard<'cx, D> {
+    output: Value<'cx, Option<D>>,
+    #[allow(clippy::type_complexity)]
+    inputs: Vec<(Value<'cx, Option<D>>, Value<'cx, Option<D>>)>,
+    eq: Compare<D>,
+}
+
+impl<D> Guard<'_, D>
+where
+    D: Clone,
+{
+    pub async fn check(self) -> Option<Option<D>> {
+        for (input, previous) in self.inputs {
+            if !(self.eq)(&input.await, &previous.await) {
+                return None;
+            }
+        }
+
+        Some(self.output.await)
+    }
+}
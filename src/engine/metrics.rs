@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::dag::Dag;
+use crate::dag::LongestPath;
+
+/// Structural metrics of the tasks, leaving out external inputs.
+#[derive(Clone, Debug)]
+pub struct Metrics<I> {
+    pub depth: usize,
+    pub width: usize,
+    pub layers: HashMap<I, usize>,
+    /// The chain of tasks with the highest total `Task::cost`.
+    pub critical_path: LongestPath<I>,
+}
+
+impl<I> Metrics<I> {
+    pub fn new() -> Self {
+        Self {
+            depth: 0,
+            width: 0,
+            layers: HashMap::new(),
+            critical_path: LongestPath::new(),
+        }
+    }
+}
+
+impl<I> Default for Metrics<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Metrics<I>
+where
+    I: Clone + Eq + Hash,
+{
+    pub(crate) fn from_dag<V, E, F>(dag: &Dag<I, V, E>, inputs: &HashSet<I>, cost: F) -> Self
+    where
+        V: Clone,
+        E: Clone,
+        F: Fn(&I) -> u64,
+    {
+        let mut builder = dag.clone().into_builder();
+        for input in inputs {
+            builder.remove_node(input);
+        }
+
+        let dag = builder.build().unwrap();
+        Self {
+            depth: dag.depth(),
+            width: dag.width(),
+            layers: dag.layers(),
+            critical_path: dag.longest_path(cost),
+        }
+    }
+}
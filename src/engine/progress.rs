@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use super::TaskReport;
+
+pub trait Observer<I>: Send + Sync {
+    /// Called when the task is first polled, which may be before its data
+    /// inputs have resolved.
+    fn on_start(&self, run_id: u64, id: &I) {
+        let _ = (run_id, id);
+    }
+
+    fn on_progress(&self, run_id: u64, id: &I, progress: &TaskProgress) {
+        let _ = (run_id, id, progress);
+    }
+
+    fn on_finish(&self, run_id: u64, id: &I, report: &TaskReport) {
+        let _ = (run_id, id, report);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TaskProgress {
+    pub done: u64,
+    pub total: u64,
+    pub message: String,
+}
+
+impl TaskProgress {
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.done.min(self.total) as f64) / (self.total as f64)
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TaskStatus {
+    Pending,
+    /// Polled at least once, possibly still waiting on its inputs.
+    Running(Option<TaskProgress>),
+    Done,
+}
+
+/// Tracks the tasks of a run, weighted by `Task::cost`. It can be read while
+/// the run is pending; lazy tasks that are never awaited stay pending.
+pub struct Progress<I> {
+    tasks: Arc<Mutex<HashMap<I, (u64, TaskStatus)>>>,
+}
+
+impl<I> Progress<I> {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.sum(|_| true)
+    }
+
+    pub fn pending(&self) -> u64 {
+        self.sum(|status| *status == TaskStatus::Pending)
+    }
+
+    pub fn running(&self) -> u64 {
+        self.sum(|status| matches!(status, TaskStatus::Running(_)))
+    }
+
+    pub fn done(&self) -> u64 {
+        self.sum(|status| *status == TaskStatus::Done)
+    }
+
+    /// Counts running tasks by the fraction they have reported so far.
+    pub fn fraction(&self) -> f64 {
+        let tasks = self.tasks.lock().unwrap();
+        let (done, total) = tasks
+            .values()
+            .fold((0.0, 0), |(done, total), (cost, status)| {
+                let fraction = match status {
+                    TaskStatus::Pending | TaskStatus::Running(None) => 0.0,
+                    TaskStatus::Running(Some(progress)) => progress.fraction(),
+                    TaskStatus::Done => 1.0,
+                };
+
+                (done + fraction * *cost as f64, total + cost)
+            });
+
+        if total == 0 { 0.0 } else { done / total as f64 }
+    }
+
+    fn sum<F>(&self, f: F) -> u64
+    where
+        F: Fn(&TaskStatus) -> bool,
+    {
+        self.tasks
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(_, status)| f(status))
+            .map(|(cost, _)| cost)
+            .sum()
+    }
+}
+
+impl<I> Progress<I>
+where
+    I: Eq + Hash,
+{
+    pub fn status(&self, id: &I) -> Option<TaskStatus> {
+        let tasks = self.tasks.lock().unwrap();
+        tasks.get(id).map(|(_, status)| status.clone())
+    }
+
+    pub(crate) fn set(&self, id: I, cost: u64, status: TaskStatus) {
+        self.tasks.lock().unwrap().insert(id, (cost, status));
+    }
+
+    pub(crate) fn update(&self, id: &I, status: TaskStatus) {
+        if let Some((_, previous)) = self.tasks.lock().unwrap().get_mut(id) {
+            *previous = status;
+        }
+    }
+}
+
+impl<I> Clone for Progress<I> {
+    fn clone(&self) -> Self {
+        Self {
+            tasks: self.tasks.clone(),
+        }
+    }
+}
+
+impl<I> Default for Progress<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) struct Reporter<I> {
+    run_id: u64,
+    observer: Option<Arc<dyn Observer<I>>>,
+    progress: Option<Progress<I>>,
+    reports: Arc<Mutex<HashMap<I, TaskReport>>>,
+    // Behind a mutex so that the reporter is `Sync` when only `I: Send`.
+    parent: Option<Arc<Mutex<Parent<I>>>>,
+}
+
+/// The run of a `SubEngine` that a child run forwards its events to, under the
+/// ids its tasks are mapped to. Tasks missing from `ids` are not forwarded,
+/// but the progress of the whole child run still is, as that of `id`.
+pub(crate) struct Parent<I> {
+    id: I,
+    ids: HashMap<I, I>,
+    reporter: Reporter<I>,
+}
+
+impl<I> Parent<I> {
+    pub fn new(id: I, ids: HashMap<I, I>, reporter: Reporter<I>) -> Self {
+        Self { id, ids, reporter }
+    }
+}
+
+impl<I> Reporter<I> {
+    pub fn new(
+        run_id: u64,
+        observer: Option<Arc<dyn Observer<I>>>,
+        progress: Option<Progress<I>>,
+    ) -> Self {
+        Self {
+            run_id,
+            observer,
+            progress,
+            reports: Arc::new(Mutex::new(HashMap::new())),
+            parent: None,
+        }
+    }
+
+    pub fn with_parent(mut self, parent: Option<Parent<I>>) -> Self {
+        self.parent = parent.map(|parent| Arc::new(Mutex::new(parent)));
+        self
+    }
+}
+
+impl<I> Reporter<I>
+where
+    I: Clone + Eq + Hash,
+{
+    pub fn start(&self, id: &I) {
+        if let Some(progress) = &self.progress {
+            progress.update(id, TaskStatus::Running(None));
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_start(self.run_id, id);
+        }
+
+        self.forward(id, |reporter, id| reporter.start(id));
+    }
+
+    pub fn skip(&self, id: &I) {
+        if let Some(progress) = &self.progress {
+            progress.update(id, TaskStatus::Done);
+        }
+
+        self.forward(id, |reporter, id| reporter.skip(id));
+
+        self.forward_progress();
+    }
+
+    pub fn progress(&self, id: &I, task_progress: TaskProgress) {
+        if let Some(observer) = &self.observer {
+            observer.on_progress(self.run_id, id, &task_progress);
+        }
+
+        self.forward(id, |reporter, id| {
+            reporter.progress(id, task_progress.clone())
+        });
+
+        if let Some(progress) = &self.progress {
+            progress.update(id, TaskStatus::Running(Some(task_progress)));
+        }
+    }
+
+    pub fn finish(&self, id: I, report: TaskReport) {
+        if let Some(progress) = &self.progress {
+            progress.update(&id, TaskStatus::Done);
+        }
+
+        if let Some(observer) = &self.observer {
+            observer.on_finish(self.run_id, &id, &report);
+        }
+
+        self.forward(&id, |reporter, id| reporter.finish(id.clone(), report));
+
+        self.reports.lock().unwrap().insert(id, report);
+        self.forward_progress();
+    }
+
+    pub fn reports(&self) -> HashMap<I, TaskReport> {
+        self.reports.lock().unwrap().clone()
+    }
+
+    fn forward<F>(&self, id: &I, f: F)
+    where
+        F: FnOnce(&Self, &I),
+    {
+        if let Some(parent) = &self.parent {
+            let parent = parent.lock().unwrap();
+            if let Some(id) = parent.ids.get(id) {
+                f(&parent.reporter, id);
+            }
+        }
+    }
+
+    fn forward_progress(&self) {
+        if let Some(parent) = &self.parent
+            && let Some(progress) = &self.progress
+        {
+            let parent = parent.lock().unwrap();
+            parent.reporter.progress(
+                &parent.id,
+                TaskProgress {
+                    done: progress.done(),
+                    total: progress.total(),
+                    message: String::new(),
+                },
+            );
+        }
+    }
+}
+
+impl<I> Clone for Reporter<I> {
+    fn clone(&self) -> Self {
+        Self {
+            run_id: self.run_id,
+            observer: self.observer.clone(),
+            progress: self.progress.clone(),
+            reports: self.reports.clone(),
+            parent: self.parent.clone(),
+        }
+    }
+}
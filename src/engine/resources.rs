@@ -0,0 +1,91 @@
+use std::any::Any;
+use std::any::TypeId;
+use std::any::type_name;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+
+#[derive(Clone, Copy)]
+pub struct ResourceId {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl ResourceId {
+    pub fn of<T>() -> Self
+    where
+        T: Any,
+    {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: type_name::<T>(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl PartialEq for ResourceId {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl Eq for ResourceId {}
+
+impl Hash for ResourceId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state);
+    }
+}
+
+impl fmt::Debug for ResourceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.type_name)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Resources {
+    resources: HashMap<ResourceId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, id: &ResourceId) -> bool {
+        self.resources.contains_key(id)
+    }
+
+    pub fn insert<T>(&mut self, resource: T)
+    where
+        T: Any + Send + Sync,
+    {
+        self.resources
+            .insert(ResourceId::of::<T>(), Arc::new(resource));
+    }
+
+    pub fn get<T>(&self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.resources
+            .get(&ResourceId::of::<T>())?
+            .clone()
+            .downcast()
+            .ok()
+    }
+
+    // Keeps the resources that are already present.
+    pub fn extend(&mut self, other: Resources) {
+        for (id, resource) in other.resources {
+            self.resources.entry(id).or_insert(resource);
+        }
+    }
+}
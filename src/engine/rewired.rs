@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::engine::ResourceId;
+use crate::task::DynTask;
+use crate::task::Input;
+use crate::task::Scope;
+use crate::task::Task;
+
+pub struct Rewired<'a, I, D> {
+    id: I,
+    dependencies: HashMap<I, I>,
+    after: Vec<I>,
+    task: Box<DynTask<'a, I, D>>,
+}
+
+impl<'a, I, D> Rewired<'a, I, D>
+where
+    I: Clone + Eq + Hash + Send + Sync + 'a,
+    D: Send + Sync + 'a,
+{
+    pub fn new(
+        id: I,
+        task: Box<DynTask<'a, I, D>>,
+        dependencies: HashMap<I, I>,
+        after: Vec<I>,
+    ) -> Self {
+        Self {
+            id,
+            dependencies,
+            after,
+            task,
+        }
+    }
+
+    pub fn namespaced(id: I, task: Box<DynTask<'a, I, D>>, ids: &HashMap<I, I>) -> Self {
+        let namespaced = |id: &I| ids.get(id).unwrap_or(id).clone();
+        let dependencies = task
+            .dependencies()
+            .into_iter()
+            .map(|dependency| (namespaced(&dependency), dependency))
+            .collect();
+
+        let after = task.after().iter().map(namespaced).collect();
+        Self::new(id, task, dependencies, after)
+    }
+}
+
+impl<I, D> Task<I, D> for Rewired<'_, I, D>
+where
+    I: Clone + Eq + Hash + Send + Sync,
+    D: Send + Sync,
+{
+    fn id(&self) -> I {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<I> {
+        self.dependencies.keys().cloned().collect()
+    }
+
+    fn after(&self) -> Vec<I> {
+        self.after.clone()
+    }
+
+    fn is_auto(&self) -> bool {
+        self.task.is_auto()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.task.is_cacheable()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.task.is_streaming()
+    }
+
+    fn stream_capacity(&self) -> usize {
+        self.task.stream_capacity()
+    }
+
+    fn resources(&self) -> Vec<ResourceId> {
+        self.task.resources()
+    }
+
+    fn cost(&self) -> u64 {
+        self.task.cost()
+    }
+
+    async fn run(&self, inputs: HashMap<I, Input<'_, D>>, scope: Scope<I, D>) -> Option<D> {
+        let inputs = inputs
+            .into_iter()
+            .flat_map(|(id, input)| Some((self.dependencies.get(&id)?.clone(), input)))
+            .collect();
+
+        self.task.run(inputs, scope.rekey(&self.dependencies)).await
+    }
+}
@@ -0,0 +1,154 @@
+use std::num::NonZeroUsize;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
+use std::task::Context;
+use std::thread;
+
+use futures::future::BoxFuture;
+use futures::task::ArcWake;
+use futures::task::waker_ref;
+
+pub trait Spawner: Send + Sync {
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Runs `f` where it cannot stall the futures driven by `spawn`, which is
+    /// a thread of its own by default.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        thread::spawn(f);
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A fixed set of std threads that run both spawned futures and blocking
+/// closures from one queue. The threads exit once the pool and every future
+/// spawned on it have been dropped. A panicking job is dropped without taking
+/// its thread down, so whoever awaits its result sees a canceled channel.
+pub struct ThreadPool {
+    sender: Sender<Job>,
+}
+
+impl ThreadPool {
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for _ in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            thread::spawn(move || {
+                loop {
+                    // Releases the lock before running the job.
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => {
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => break,
+                    }
+                }
+            });
+        }
+
+        Self { sender }
+    }
+}
+
+impl Default for ThreadPool {
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, NonZeroUsize::get))
+    }
+}
+
+impl Spawner for ThreadPool {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        Arc::new(Spawned {
+            future: Mutex::new(Some(future)),
+            sender: self.sender.clone(),
+        })
+        .schedule();
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        let _ = self.sender.send(f);
+    }
+}
+
+struct Spawned {
+    future: Mutex<Option<BoxFuture<'static, ()>>>,
+    sender: Sender<Job>,
+}
+
+impl Spawned {
+    fn schedule(self: Arc<Self>) {
+        let _ = self.sender.clone().send(Box::new(move || self.poll()));
+    }
+
+    fn poll(self: &Arc<Self>) {
+        // A future that panicked was taken out of the slot before polling.
+        let mut slot = self.future.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(mut future) = slot.take() {
+            let waker = waker_ref(self);
+            if future
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending()
+            {
+                *slot = Some(future);
+            }
+        }
+    }
+}
+
+impl ArcWake for Spawned {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.clone().schedule();
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub struct TokioSpawner {
+    handle: tokio::runtime::Handle,
+}
+
+#[cfg(feature = "tokio")]
+impl TokioSpawner {
+    pub fn new(handle: tokio::runtime::Handle) -> Self {
+        Self { handle }
+    }
+
+    /// Panics outside of a tokio runtime.
+    pub fn current() -> Self {
+        Self::new(tokio::runtime::Handle::current())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Spawner for TokioSpawner {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        self.handle.spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        self.handle.spawn_blocking(f);
+    }
+}
+
+#[cfg(feature = "async-std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsyncStdSpawner;
+
+#[cfg(feature = "async-std")]
+impl Spawner for AsyncStdSpawner {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        async_std::task::spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        async_std::task::spawn_blocking(f);
+    }
+}
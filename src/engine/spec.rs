@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::BuildEngineError;
+use super::EngineBuilder;
+use super::EngineErrorKind;
+use super::Rewired;
+use crate::task::DynTask;
+use crate::task::Task;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WorkflowSpec<I, P> {
+    #[serde(default)]
+    pub inputs: Vec<I>,
+    pub tasks: Vec<TaskSpec<I, P>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TaskSpec<I, P> {
+    pub id: I,
+    pub kind: String,
+    #[serde(default)]
+    pub dependencies: Vec<I>,
+    #[serde(default)]
+    pub after: Vec<I>,
+    pub params: Option<P>,
+}
+
+impl<I, P> WorkflowSpec<I, P>
+where
+    I: Clone + Eq + Hash + Send + Sync,
+{
+    pub fn into_builder<'a, D>(
+        self,
+        registry: &Registry<'a, I, D, P>,
+    ) -> Result<EngineBuilder<'a, I, D>, BuildEngineError<I>>
+    where
+        I: 'a,
+        D: Send + Sync + 'a,
+    {
+        let mut unknown_kinds = Vec::new();
+        for TaskSpec { kind, .. } in &self.tasks {
+            if !registry.contains(kind) && !unknown_kinds.contains(kind) {
+                unknown_kinds.push(kind.clone());
+            }
+        }
+
+        if !unknown_kinds.is_empty() {
+            Err(EngineErrorKind::UnknownKinds(unknown_kinds))?
+        }
+
+        let builder = EngineBuilder::new();
+        for id in self.inputs {
+            builder.add_input(id);
+        }
+
+        for spec in &self.tasks {
+            let task = (registry.factories[&spec.kind])(spec);
+            let dependencies = spec
+                .dependencies
+                .iter()
+                .map(|dependency| (dependency.clone(), dependency.clone()))
+                .collect();
+
+            builder.add_task(Rewired::new(
+                spec.id.clone(),
+                task,
+                dependencies,
+                spec.after.clone(),
+            ));
+        }
+
+        Ok(builder)
+    }
+}
+
+type Factory<'a, I, D, P> = Box<dyn Fn(&TaskSpec<I, P>) -> Box<DynTask<'a, I, D>> + 'a>;
+
+pub struct Registry<'a, I, D, P> {
+    factories: HashMap<String, Factory<'a, I, D, P>>,
+}
+
+impl<I, D, P> Registry<'_, I, D, P> {
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    pub fn contains(&self, kind: &str) -> bool {
+        self.factories.contains_key(kind)
+    }
+}
+
+impl<I, D, P> Default for Registry<'_, I, D, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, I, D, P> Registry<'a, I, D, P> {
+    pub fn register<F, T>(&mut self, kind: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn(&TaskSpec<I, P>) -> T + 'a,
+        T: Task<I, D> + 'a,
+    {
+        self.factories.insert(
+            kind.into(),
+            Box::new(move |spec| DynTask::new_box(factory(spec))),
+        );
+
+        self
+    }
+}
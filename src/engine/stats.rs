@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::CacheStatus;
+
+const BUCKETS: usize = 48;
+
+#[derive(Clone, Debug)]
+pub struct Report<I> {
+    pub run_id: u64,
+    pub tasks: HashMap<I, TaskReport>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TaskReport {
+    /// Time from the first poll of the task, after its `after` dependencies,
+    /// to its output. Inputs are awaited inside `run`, so this includes time
+    /// spent waiting on upstream tasks.
+    pub latency: Duration,
+    pub cache: Option<CacheStatus>,
+}
+
+// Bucket `i` counts latencies in `[2^(i - 1), 2^i)` microseconds.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    count: u64,
+    sum: Duration,
+    min: Duration,
+    max: Duration,
+    buckets: [u64; BUCKETS],
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            sum: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+            buckets: [0; BUCKETS],
+        }
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        self.count += 1;
+        self.sum += latency;
+        self.min = self.min.min(latency);
+        self.max = self.max.max(latency);
+
+        let micros = latency.as_micros().min(u64::MAX as _) as u64;
+        let bucket = (u64::BITS - micros.leading_zeros()) as usize;
+        self.buckets[bucket.min(BUCKETS - 1)] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| self.sum.div_f64(self.count as _))
+    }
+
+    // Returns the upper bound of the bucket holding the `q`-th quantile.
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+
+        for (upper_bound, count) in self.buckets() {
+            seen += count;
+            if seen >= rank {
+                return Some(upper_bound.min(self.max));
+            }
+        }
+
+        Some(self.max)
+    }
+
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(bucket, &count)| (Duration::from_micros(1 << bucket), count))
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use futures::FutureExt;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+
+use super::Engine;
+use super::Options;
+use super::Parent;
+use super::Progress;
+use crate::context::Context;
+use crate::task::Input;
+use crate::task::Scope;
+use crate::task::Task;
+
+type Output<'a, I, D> = Box<dyn Fn(HashMap<I, Option<D>>) -> Option<D> + Send + Sync + 'a>;
+
+pub struct SubEngine<'a, I, D> {
+    id: I,
+    engine: Engine<'a, I, D>,
+    inputs: Vec<(I, I)>,
+    outputs: Vec<I>,
+    output: Output<'a, I, D>,
+    is_auto: bool,
+    ids: HashMap<I, I>,
+}
+
+impl<'a, I, D> SubEngine<'a, I, D> {
+    pub fn new(id: I, engine: Engine<'a, I, D>) -> Self {
+        Self {
+            id,
+            engine,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            output: Box::new(|_| None),
+            is_auto: true,
+            ids: HashMap::new(),
+        }
+    }
+
+    pub fn input(mut self, dependency: I, id: I) -> Self {
+        self.inputs.push((dependency, id));
+        self
+    }
+
+    pub fn outputs<F>(mut self, ids: Vec<I>, output: F) -> Self
+    where
+        F: Fn(HashMap<I, Option<D>>) -> Option<D> + Send + Sync + 'a,
+    {
+        self.outputs = ids;
+        self.output = Box::new(output);
+        self
+    }
+
+    pub fn auto(mut self, is_auto: bool) -> Self {
+        self.is_auto = is_auto;
+        self
+    }
+}
+
+impl<'a, I, D> SubEngine<'a, I, D>
+where
+    I: Eq + Hash + 'a,
+    D: 'a,
+{
+    pub fn output(self, id: I) -> Self {
+        self.outputs(vec![id], |mut outputs| {
+            outputs.drain().next().and_then(|(_, output)| output)
+        })
+    }
+}
+
+impl<I, D> SubEngine<'_, I, D>
+where
+    I: Clone + Eq + Hash,
+{
+    /// Forwards the reports and observer events of the child tasks to the
+    /// parent run under `namespace(id)`, such as `parent/child`. Without it,
+    /// the parent only sees the progress of the child run as a whole.
+    pub fn namespace<F>(mut self, namespace: F) -> Self
+    where
+        F: Fn(&I) -> I,
+    {
+        self.ids = self
+            .engine
+            .tasks
+            .keys()
+            .map(|id| (id.clone(), namespace(id)))
+            .collect();
+
+        self
+    }
+}
+
+impl<'a, I, D> Task<I, D> for SubEngine<'a, I, D>
+where
+    I: Clone + Eq + Hash + Send + Sync + 'a,
+    D: Clone + Send + Sync + 'a,
+{
+    fn id(&self) -> I {
+        self.id.clone()
+    }
+
+    fn dependencies(&self) -> Vec<I> {
+        self.inputs
+            .iter()
+            .map(|(dependency, _)| dependency.clone())
+            .collect()
+    }
+
+    fn is_auto(&self) -> bool {
+        self.is_auto
+    }
+
+    async fn run(&self, inputs: HashMap<I, Input<'_, D>>, scope: Scope<I, D>) -> Option<D> {
+        // The child run sees the same parameters as the parent run.
+        let context = Context::new().with_shared_params(scope.shared_params());
+        for (dependency, id) in &self.inputs {
+            if let Some(input) = inputs.get(dependency) {
+                context.set(id.clone(), input.clone().boxed().shared());
+            }
+        }
+
+        let parent = Parent::new(
+            scope.id().clone(),
+            self.ids.clone(),
+            scope.reporter().clone(),
+        );
+        let progress = Progress::new();
+        self.engine
+            .execute(
+                context.clone(),
+                Options {
+                    progress: Some(&progress),
+                    parent: Some(parent),
+                    ..Options::default()
+                },
+            )
+            .await;
+
+        let outputs = self
+            .outputs
+            .iter()
+            .flat_map(|id| {
+                let output = context.get(id)?;
+                Some(async move { (id.clone(), output.await) })
+            })
+            .collect::<FuturesUnordered<_>>()
+            .collect()
+            .await;
+
+        (self.output)(outputs)
+    }
+}
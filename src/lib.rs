@@ -1,3 +1,4 @@
 pub mod context;
+pub mod dag;
 pub mod engine;
 pub mod task;
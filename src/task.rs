@@ -1,6 +1,24 @@
+use std::any::Any;
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
 
+use futures::channel::oneshot;
+
+use crate::context::Params;
 use crate::context::Value;
+use crate::engine::Reporter;
+use crate::engine::ResourceId;
+use crate::engine::Resources;
+use crate::engine::Spawner;
+use crate::engine::TaskProgress;
+
+mod stream;
+pub(crate) use stream::Closer;
+pub(crate) use stream::Publisher;
+pub use stream::Subscription;
+pub(crate) use stream::channel;
 
 pub type Input<'a, T> = Value<'a, Option<T>>;
 
@@ -13,9 +31,219 @@ pub trait Task<I, D> {
         Vec::new()
     }
 
+    /// Tasks that must finish before this one starts, without their outputs
+    /// being passed in as inputs. An id listed in both `dependencies` and
+    /// `after` is an ordinary dependency.
+    fn after(&self) -> Vec<I> {
+        Vec::new()
+    }
+
     fn is_auto(&self) -> bool {
         true
     }
 
-    async fn run(&self, inputs: HashMap<I, Input<'_, D>>) -> Option<D>;
+    /// Whether the engine cache may reuse an output for the same inputs. The
+    /// cache is skipped in runs with params, which are not part of the key.
+    fn is_cacheable(&self) -> bool {
+        false
+    }
+
+    /// A streaming task publishes items with `Scope::send` while it runs, and
+    /// each dependent can read them through `Scope::subscribe`. The stream ends
+    /// when `run` returns. Sending waits while the slowest subscriber is
+    /// `stream_capacity` items behind, so subscribers that stop reading should
+    /// drop their subscriptions. Dependents that have not subscribed never
+    /// hold sending back, but items are kept for them until they subscribe or
+    /// finish. Since reading a subscription does not poll the task, a
+    /// streaming task always runs, even if it is not auto.
+    fn is_streaming(&self) -> bool {
+        false
+    }
+
+    fn stream_capacity(&self) -> usize {
+        16
+    }
+
+    /// Resources that must be registered with `EngineBuilder::resource` for the
+    /// engine to build, fetched in `run` with `Scope::resource`.
+    fn resources(&self) -> Vec<ResourceId> {
+        Vec::new()
+    }
+
+    /// The relative weight of this task in `Progress`.
+    fn cost(&self) -> u64 {
+        1
+    }
+
+    async fn run(&self, inputs: HashMap<I, Input<'_, D>>, scope: Scope<I, D>) -> Option<D>;
+}
+
+pub struct Scope<I, D> {
+    id: I,
+    run_id: u64,
+    publisher: Option<Publisher<D>>,
+    subscriptions: Arc<Mutex<HashMap<I, Subscription<D>>>>,
+    reporter: Reporter<I>,
+    resources: Arc<Resources>,
+    params: Option<Params>,
+    spawner: Option<Arc<dyn Spawner>>,
+}
+
+impl<I, D> Scope<I, D> {
+    pub fn new(id: I, run_id: u64) -> Self {
+        Self {
+            id,
+            run_id,
+            publisher: None,
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            reporter: Reporter::new(run_id, None, None),
+            resources: Arc::new(Resources::new()),
+            params: None,
+            spawner: None,
+        }
+    }
+
+    pub(crate) fn with_spawner(mut self, spawner: Option<Arc<dyn Spawner>>) -> Self {
+        self.spawner = spawner;
+        self
+    }
+
+    pub(crate) fn with_resources(mut self, resources: Arc<Resources>) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    pub(crate) fn with_reporter(mut self, reporter: Reporter<I>) -> Self {
+        self.reporter = reporter;
+        self
+    }
+
+    pub(crate) fn with_streams(
+        mut self,
+        publisher: Option<Publisher<D>>,
+        subscriptions: HashMap<I, Subscription<D>>,
+    ) -> Self {
+        self.publisher = publisher;
+        self.subscriptions = Arc::new(Mutex::new(subscriptions));
+        self
+    }
+
+    pub fn id(&self) -> &I {
+        &self.id
+    }
+
+    pub fn run_id(&self) -> u64 {
+        self.run_id
+    }
+
+    pub(crate) fn with_params(mut self, params: Option<Params>) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub(crate) fn shared_params(&self) -> Option<Params> {
+        self.params.clone()
+    }
+
+    pub(crate) fn reporter(&self) -> &Reporter<I> {
+        &self.reporter
+    }
+
+    /// The parameters attached to the run's `Context` with `with_params`.
+    pub fn params<P>(&self) -> Option<Arc<P>>
+    where
+        P: Any + Send + Sync,
+    {
+        self.params.clone()?.downcast().ok()
+    }
+
+    pub fn resource<T>(&self) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        self.resources.get()
+    }
+
+    pub(crate) fn has_spawner(&self) -> bool {
+        self.spawner.is_some()
+    }
+
+    /// Runs `f` with the engine's `Spawner`, or in place if there is none.
+    pub async fn spawn_blocking<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let Some(spawner) = &self.spawner else {
+            return f();
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        spawner.spawn_blocking(Box::new(move || {
+            let _ = sender.send(f());
+        }));
+
+        receiver.await.expect("blocking closure panicked")
+    }
+
+    pub async fn send(&self, item: D) -> bool {
+        match &self.publisher {
+            Some(publisher) => publisher.send(item).await,
+            None => false,
+        }
+    }
+}
+
+impl<I, D> Scope<I, D>
+where
+    I: Clone + Eq + Hash,
+{
+    /// Forwards to the engine's `Observer` and to the run's `Progress`.
+    pub fn progress(&self, done: u64, total: u64, message: impl Into<String>) {
+        self.reporter.progress(
+            &self.id,
+            TaskProgress {
+                done,
+                total,
+                message: message.into(),
+            },
+        );
+    }
+
+    pub fn subscribe(&self, id: &I) -> Option<Subscription<D>> {
+        let subscription = self.subscriptions.lock().unwrap().remove(id)?;
+        subscription.claim();
+        Some(subscription)
+    }
+
+    pub(crate) fn rekey(self, ids: &HashMap<I, I>) -> Self {
+        let subscriptions = self
+            .subscriptions
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(id, subscription)| (ids.get(&id).cloned().unwrap_or(id), subscription))
+            .collect();
+
+        let publisher = self.publisher.clone();
+        self.with_streams(publisher, subscriptions)
+    }
+}
+
+impl<I, D> Clone for Scope<I, D>
+where
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            run_id: self.run_id,
+            publisher: self.publisher.clone(),
+            subscriptions: self.subscriptions.clone(),
+            reporter: self.reporter.clone(),
+            resources: self.resources.clone(),
+            params: self.params.clone(),
+            spawner: self.spawner.clone(),
+        }
+    }
 }
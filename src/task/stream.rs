@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Waker;
+
+use futures::Stream;
+
+pub(crate) fn channel<D>(
+    subscribers: usize,
+    capacity: usize,
+) -> (Publisher<D>, Vec<Subscription<D>>) {
+    let state = Arc::new(Mutex::new(State {
+        buffer: VecDeque::new(),
+        offset: 0,
+        cursors: vec![Some(0); subscribers],
+        claimed: vec![false; subscribers],
+        capacity: capacity.max(1),
+        is_closed: false,
+        publishers: Vec::new(),
+        subscribers: vec![None; subscribers],
+    }));
+
+    let subscriptions = (0..subscribers)
+        .map(|index| Subscription {
+            index,
+            state: state.clone(),
+        })
+        .collect();
+
+    (Publisher { state }, subscriptions)
+}
+
+struct State<D> {
+    buffer: VecDeque<D>,
+    offset: usize,
+    cursors: Vec<Option<usize>>,
+    // Only claimed subscriptions hold the publisher back, while items are kept
+    // for every live one.
+    claimed: Vec<bool>,
+    capacity: usize,
+    is_closed: bool,
+    publishers: Vec<Waker>,
+    subscribers: Vec<Option<Waker>>,
+}
+
+impl<D> State<D> {
+    fn min_cursor(&self) -> Option<usize> {
+        self.cursors.iter().flatten().min().copied()
+    }
+
+    fn min_claimed_cursor(&self) -> Option<usize> {
+        self.cursors
+            .iter()
+            .zip(&self.claimed)
+            .flat_map(|(cursor, &claimed)| cursor.filter(|_| claimed))
+            .min()
+    }
+
+    fn trim(&mut self) {
+        let end = self.offset + self.buffer.len();
+        let min_cursor = self.min_cursor().unwrap_or(end);
+
+        while self.offset < min_cursor {
+            self.buffer.pop_front();
+            self.offset += 1;
+        }
+
+        for waker in self.publishers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn wake_subscribers(&mut self) {
+        for waker in self.subscribers.iter_mut().flat_map(Option::take) {
+            waker.wake();
+        }
+    }
+}
+
+pub(crate) struct Publisher<D> {
+    state: Arc<Mutex<State<D>>>,
+}
+
+impl<D> Publisher<D> {
+    // Resolves to `false` without buffering `item` once every subscription has
+    // been dropped or the stream has been closed.
+    pub fn send(&self, item: D) -> Sending<'_, D> {
+        Sending {
+            publisher: self,
+            item: Some(item),
+        }
+    }
+
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.is_closed = true;
+        state.wake_subscribers();
+    }
+}
+
+pub(crate) struct Closer<D>(pub Publisher<D>);
+
+impl<D> Drop for Closer<D> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+impl<D> Clone for Publisher<D> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+pub(crate) struct Sending<'a, D> {
+    publisher: &'a Publisher<D>,
+    item: Option<D>,
+}
+
+impl<D> Unpin for Sending<'_, D> {}
+
+impl<D> Future for Sending<'_, D> {
+    type Output = bool;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.publisher.state.lock().unwrap();
+        if state.is_closed {
+            return Poll::Ready(false);
+        }
+
+        if state.min_cursor().is_none() {
+            return Poll::Ready(false);
+        }
+
+        if let Some(min_cursor) = state.min_claimed_cursor()
+            && state.offset + state.buffer.len() - min_cursor >= state.capacity
+        {
+            state.publishers.push(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let item = self.item.take().unwrap();
+        state.buffer.push_back(item);
+        state.wake_subscribers();
+
+        Poll::Ready(true)
+    }
+}
+
+pub struct Subscription<D> {
+    index: usize,
+    state: Arc<Mutex<State<D>>>,
+}
+
+impl<D> Subscription<D> {
+    pub(crate) fn claim(&self) {
+        self.state.lock().unwrap().claimed[self.index] = true;
+    }
+}
+
+impl<D> Stream for Subscription<D>
+where
+    D: Clone,
+{
+    type Item = D;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.lock().unwrap();
+        let cursor = state.cursors[self.index].unwrap();
+
+        if cursor < state.offset + state.buffer.len() {
+            let item = state.buffer[cursor - state.offset].clone();
+            state.cursors[self.index] = Some(cursor + 1);
+            state.trim();
+
+            return Poll::Ready(Some(item));
+        }
+
+        if state.is_closed {
+            return Poll::Ready(None);
+        }
+
+        state.subscribers[self.index] = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<D> Drop for Subscription<D> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.cursors[self.index] = None;
+        state.subscribers[self.index] = None;
+        state.trim();
+    }
+}